@@ -0,0 +1,98 @@
+//! Dijkstra across a 1000x1000 grid of random digits with each heap.
+//!
+//! Run with `cargo bench --bench heaps`.
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    time::{Duration, Instant},
+};
+
+use aoc2025::aoc::{
+    grid::{Direction, Grid, Point},
+    heap::{IndexedHeap, MinHeap, RadixHeap},
+};
+
+const SIZE: usize = 1000;
+const RUNS: u32 = 5;
+
+fn random_grid() -> Grid<Vec<u8>> {
+    let mut seed = 0x9e3779b97f4a7c15u64;
+    let items = (0..SIZE * SIZE)
+        .map(|_| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as u8 % 9 + 1
+        })
+        .collect();
+    Grid::new(SIZE, SIZE, items)
+}
+
+/// Cheapest cost from the top left to the bottom right and the largest the
+/// heap got.
+fn dijkstra<H: MinHeap<Key = u64>>(grid: &Grid<Vec<u8>>, mut heap: H) -> (u64, usize) {
+    let width = grid.width();
+    let target = Point::new(width - 1, grid.height() - 1);
+    let mut distance = vec![u64::MAX; grid.len()];
+    let mut peak = 0;
+    distance[0] = 0;
+    heap.push(0, 0);
+    while let Some((i, d)) = heap.pop() {
+        if d > distance[i] {
+            continue;
+        }
+        let p = Point::new(i % width, i / width);
+        if p == target {
+            return (d, peak);
+        }
+        for direction in Direction::all() {
+            let Some(cell) = p.go(direction).and_then(|n| n.on(grid)) else {
+                continue;
+            };
+            let next = d + *cell.value() as u64;
+            let j = cell.y * width + cell.x;
+            if next < distance[j] {
+                distance[j] = next;
+                heap.push(j, next);
+                peak = peak.max(heap.len());
+            }
+        }
+    }
+    panic!("Target unreachable");
+}
+
+fn bench<H, F>(name: &str, grid: &Grid<Vec<u8>>, make: F) -> u64
+where
+    H: MinHeap<Key = u64>,
+    F: Fn() -> H,
+{
+    let mut total = Duration::ZERO;
+    let mut result = (0, 0);
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        result = std::hint::black_box(dijkstra(grid, make()));
+        total += start.elapsed();
+    }
+    println!(
+        "{name:<24} {:>10.2?} per run, peak {:>7} entries",
+        total / RUNS,
+        result.1
+    );
+    result.0
+}
+
+fn main() {
+    let grid = random_grid();
+    let costs = [
+        bench("BinaryHeap<Reverse<_>>", &grid, || {
+            BinaryHeap::<Reverse<(u64, usize)>>::new()
+        }),
+        bench("IndexedHeap", &grid, || IndexedHeap::new(grid.len())),
+        bench("RadixHeap", &grid, RadixHeap::new),
+    ];
+    assert!(
+        costs.iter().all(|&c| c == costs[0]),
+        "Heaps disagree: {costs:?}"
+    );
+}
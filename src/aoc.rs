@@ -1,3 +1,18 @@
 pub mod cli;
+pub mod combinatorics;
+pub mod compress;
+pub mod dp;
+pub mod dsu;
 pub mod example;
+#[cfg(test)]
+pub(crate) mod fixtures;
+pub mod geometry;
+pub mod graph;
 pub mod grid;
+pub mod heap;
+pub mod interval;
+pub mod memo;
+pub mod mst;
+pub mod sim;
+pub mod spatial;
+pub mod sweep;
@@ -9,6 +9,10 @@ use clap::Parser;
 #[derive(Parser, Debug)]
 pub struct Cli {
     pub input: Option<std::path::PathBuf>,
+
+    /// Pause simulations after every step and read commands from stdin
+    #[arg(long)]
+    pub step: bool,
 }
 
 fn input_path(filename: &str) -> std::path::PathBuf {
@@ -34,18 +38,38 @@ impl Cli {
         if let Some(f) = &self.input {
             f.clone()
         } else {
-            let day_name = env::args()
-                .next_back()
-                .unwrap()
-                .rsplit_once('/')
-                .unwrap()
-                .1
-                .to_owned();
-            input_path(&format!("{day_name}.txt"))
+            default_input(&env::args().next().unwrap())
         }
     }
 }
 
+// Named after the binary itself rather than the last argument, which may be
+// a flag like --step
+fn default_input(program: &str) -> std::path::PathBuf {
+    let day_name = program.rsplit('/').next().unwrap();
+    input_path(&format!("{day_name}.txt"))
+}
+
 pub fn parse() -> Cli {
     Cli::parse()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_step_without_input() {
+        let cli = Cli::try_parse_from(["target/debug/day1", "--step"]).unwrap();
+        assert!(cli.step);
+        assert_eq!(cli.input, None);
+        assert_eq!(
+            default_input("target/debug/day1"),
+            std::path::PathBuf::from("input/day1.txt")
+        );
+        assert_eq!(
+            default_input("day1"),
+            std::path::PathBuf::from("input/day1.txt")
+        );
+    }
+}
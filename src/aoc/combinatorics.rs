@@ -0,0 +1,498 @@
+use crate::quant::gcd;
+
+/// `n!`, or `None` if it does not fit in a `u128` (n > 34).
+pub fn factorial(n: u32) -> Option<u128> {
+    (1..=n as u128).try_fold(1u128, |acc, i| acc.checked_mul(i))
+}
+
+/// `n choose k`, or `None` if the result does not fit in a `u128`.
+pub fn binomial(n: u64, k: u64) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k) as u128;
+    let n = n as u128;
+    // Each partial product is itself a binomial coefficient, no bigger than
+    // the result as k <= n / 2. Dividing out the common factor first keeps
+    // the multiplication from overflowing before the exact division does.
+    (0..k).try_fold(1u128, |acc, i| {
+        let g = gcd(acc, i + 1);
+        (acc / g).checked_mul((n - i) / ((i + 1) / g))
+    })
+}
+
+/// `n choose k` modulo the prime `p`, using Lucas's theorem so `n` can be far
+/// larger than `p`.
+pub fn binomial_mod(n: u64, k: u64, p: u64) -> u64 {
+    let (mut n, mut k) = (n, k);
+    let mut acc = 1 % p;
+    while k > 0 {
+        let (ni, ki) = (n % p, k % p);
+        if ki > ni {
+            return 0;
+        }
+        acc = mul_mod(acc, small_binomial_mod(ni, ki, p), p);
+        n /= p;
+        k /= p;
+    }
+    acc
+}
+
+fn small_binomial_mod(n: u64, k: u64, p: u64) -> u64 {
+    let k = k.min(n - k);
+    let mut num = 1;
+    let mut den = 1;
+    for i in 0..k {
+        num = mul_mod(num, n - i, p);
+        den = mul_mod(den, i + 1, p);
+    }
+    mul_mod(num, pow_mod(den, p - 2, p), p)
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut acc = 1 % m;
+    base %= m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// Factorials and inverse factorials modulo a prime, for O(1) binomials once
+/// built.
+#[derive(Debug, Clone)]
+pub struct FactorialTable {
+    modulus: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+impl FactorialTable {
+    /// Covers `0..=max`, which must be less than the prime `modulus`.
+    pub fn new(max: usize, modulus: u64) -> Self {
+        assert!(
+            (max as u64) < modulus,
+            "Factorials up to the modulus are all zero"
+        );
+        let mut fact = vec![1 % modulus; max + 1];
+        for i in 1..=max {
+            fact[i] = mul_mod(fact[i - 1], i as u64, modulus);
+        }
+        let mut inv_fact = vec![0; max + 1];
+        inv_fact[max] = pow_mod(fact[max], modulus - 2, modulus);
+        for i in (0..max).rev() {
+            inv_fact[i] = mul_mod(inv_fact[i + 1], i as u64 + 1, modulus);
+        }
+        Self {
+            modulus,
+            fact,
+            inv_fact,
+        }
+    }
+
+    pub fn factorial(&self, n: usize) -> u64 {
+        self.fact[n]
+    }
+
+    pub fn inverse_factorial(&self, n: usize) -> u64 {
+        self.inv_fact[n]
+    }
+
+    pub fn binomial(&self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        mul_mod(
+            self.fact[n],
+            mul_mod(self.inv_fact[k], self.inv_fact[n - k], self.modulus),
+            self.modulus,
+        )
+    }
+
+    /// Number of ordered arrangements of `k` items out of `n`.
+    pub fn permutations(&self, n: usize, k: usize) -> u64 {
+        if k > n {
+            return 0;
+        }
+        mul_mod(self.fact[n], self.inv_fact[n - k], self.modulus)
+    }
+}
+
+/// Rearranges `xs` into the next permutation in lexicographic order,
+/// returning false (and leaving `xs` sorted) once the last one is reached.
+pub fn next_permutation<T: Ord>(xs: &mut [T]) -> bool {
+    let Some(pivot) = (1..xs.len()).rev().find(|&i| xs[i - 1] < xs[i]) else {
+        xs.reverse();
+        return false;
+    };
+    let pivot = pivot - 1;
+    let successor = (pivot + 1..xs.len())
+        .rev()
+        .find(|&i| xs[i] > xs[pivot])
+        .unwrap();
+    xs.swap(pivot, successor);
+    xs[pivot + 1..].reverse();
+    true
+}
+
+/// Position of `perm` among all orderings of its (distinct) items, in
+/// lexicographic order.
+pub fn permutation_rank<T: Ord>(perm: &[T]) -> u128 {
+    let n = perm.len();
+    (0..n)
+        .map(|i| {
+            let smaller_after = perm[i + 1..].iter().filter(|x| **x < perm[i]).count();
+            smaller_after as u128 * factorial((n - i - 1) as u32).expect("Too many items to rank")
+        })
+        .sum()
+}
+
+/// The ordering of `sorted` at position `rank`, the inverse of
+/// `permutation_rank`.
+pub fn permutation_unrank<T: Clone>(sorted: &[T], mut rank: u128) -> Vec<T> {
+    let mut remaining = sorted.to_vec();
+    let mut out = Vec::with_capacity(sorted.len());
+    while !remaining.is_empty() {
+        let block = factorial(remaining.len() as u32 - 1).expect("Too many items to unrank");
+        let i = (rank / block) as usize;
+        assert!(i < remaining.len(), "Rank out of range");
+        rank %= block;
+        out.push(remaining.remove(i));
+    }
+    out
+}
+
+/// A lending iterator: each item borrows a buffer owned by the iterator, so
+/// hot loops don't allocate a `Vec` per item. Fused like a `FusedIterator`:
+/// once `next` returns `None` it keeps returning `None`.
+pub trait SliceIter {
+    type Item;
+
+    fn next(&mut self) -> Option<&[Self::Item]>;
+
+    fn for_each<F: FnMut(&[Self::Item])>(mut self, mut f: F)
+    where
+        Self: Sized,
+    {
+        while let Some(items) = self.next() {
+            f(items);
+        }
+    }
+
+    fn count(mut self) -> usize
+    where
+        Self: Sized,
+    {
+        let mut n = 0;
+        while self.next().is_some() {
+            n += 1;
+        }
+        n
+    }
+}
+
+// Shared by all the iterators below: maps the current indices onto the buffer
+fn fill<'b, T: Clone>(items: &[T], indices: &[usize], buf: &'b mut Vec<T>) -> &'b [T] {
+    buf.clear();
+    buf.extend(indices.iter().map(|&i| items[i].clone()));
+    buf
+}
+
+/// All orderings of the items by position, in lexicographic order of
+/// position.
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+pub fn permutations<T: Clone>(items: &[T]) -> Permutations<'_, T> {
+    Permutations {
+        items,
+        indices: (0..items.len()).collect(),
+        buf: Vec::with_capacity(items.len()),
+        started: false,
+        done: false,
+    }
+}
+
+impl<T: Clone> SliceIter for Permutations<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            // next_permutation wraps around to the first ordering, so stop it
+            // starting over
+            if !next_permutation(&mut self.indices) {
+                self.done = true;
+                return None;
+            }
+        } else {
+            self.started = true;
+        }
+        Some(fill(self.items, &self.indices, &mut self.buf))
+    }
+}
+
+/// Subsets of size `k` in lexicographic order of position.
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+pub fn combinations<T: Clone>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations {
+        items,
+        indices: (0..k).collect(),
+        buf: Vec::with_capacity(k),
+        started: false,
+        done: k > items.len(),
+    }
+}
+
+impl<T: Clone> SliceIter for Combinations<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        let n = self.items.len();
+        let k = self.indices.len();
+        if !self.started {
+            self.started = true;
+        } else {
+            // Rightmost index that can still move up
+            let Some(i) = (0..k).rev().find(|&i| self.indices[i] < n - k + i) else {
+                self.done = true;
+                return None;
+            };
+            self.indices[i] += 1;
+            for j in i + 1..k {
+                self.indices[j] = self.indices[j - 1] + 1;
+            }
+        }
+        Some(fill(self.items, &self.indices, &mut self.buf))
+    }
+}
+
+/// Multisets of size `k`, as non-decreasing position sequences.
+pub struct CombinationsWithReplacement<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+pub fn combinations_with_replacement<T: Clone>(
+    items: &[T],
+    k: usize,
+) -> CombinationsWithReplacement<'_, T> {
+    CombinationsWithReplacement {
+        items,
+        indices: vec![0; k],
+        buf: Vec::with_capacity(k),
+        started: false,
+        done: items.is_empty() && k > 0,
+    }
+}
+
+impl<T: Clone> SliceIter for CombinationsWithReplacement<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        let n = self.items.len();
+        if !self.started {
+            self.started = true;
+        } else {
+            let Some(i) = self.indices.iter().rposition(|&x| x + 1 < n) else {
+                self.done = true;
+                return None;
+            };
+            let next = self.indices[i] + 1;
+            self.indices[i..].iter_mut().for_each(|x| *x = next);
+        }
+        Some(fill(self.items, &self.indices, &mut self.buf))
+    }
+}
+
+/// Every length `k` sequence drawn from the items, like `k` nested loops.
+pub struct CartesianPower<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    buf: Vec<T>,
+    started: bool,
+    done: bool,
+}
+
+pub fn cartesian_power<T: Clone>(items: &[T], k: usize) -> CartesianPower<'_, T> {
+    CartesianPower {
+        items,
+        indices: vec![0; k],
+        buf: Vec::with_capacity(k),
+        started: false,
+        done: items.is_empty() && k > 0,
+    }
+}
+
+impl<T: Clone> SliceIter for CartesianPower<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<&[T]> {
+        if self.done {
+            return None;
+        }
+        let n = self.items.len();
+        if !self.started {
+            self.started = true;
+        } else {
+            let Some(i) = self.indices.iter().rposition(|&x| x + 1 < n) else {
+                self.done = true;
+                return None;
+            };
+            self.indices[i] += 1;
+            self.indices[i + 1..].iter_mut().for_each(|x| *x = 0);
+        }
+        Some(fill(self.items, &self.indices, &mut self.buf))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn collect<I: SliceIter>(mut iter: I) -> Vec<Vec<I::Item>>
+    where
+        I::Item: Clone,
+    {
+        let mut out = Vec::new();
+        while let Some(items) = iter.next() {
+            out.push(items.to_vec());
+        }
+        out
+    }
+
+    fn collect_n<I: SliceIter>(iter: &mut I, n: usize) -> usize {
+        (0..n).filter(|_| iter.next().is_some()).count()
+    }
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(5, 7), Some(0));
+        assert_eq!(binomial(60, 30), Some(118264581564861424));
+        assert_eq!(binomial(200, 100), None);
+        // The last partial product overflows before its division
+        assert_eq!(
+            binomial(131, 65),
+            Some(188694833082770476622296176145946360850)
+        );
+        assert_eq!(binomial(132, 66), None);
+        assert_eq!(factorial(20), Some(2432902008176640000));
+        assert_eq!(factorial(35), None);
+
+        assert_eq!(
+            binomial_mod(60, 30, 1_000_000_007),
+            (118264581564861424u128 % 1_000_000_007) as u64
+        );
+        assert_eq!(binomial_mod(10, 3, 7), 120 % 7);
+        // 1000 choose 500 is divisible by 13 (Kummer: carries in base 13)
+        assert_eq!(binomial_mod(1000, 500, 13), 0);
+
+        let table = FactorialTable::new(100, 1_000_000_007);
+        assert_eq!(table.binomial(60, 30), binomial_mod(60, 30, 1_000_000_007));
+        assert_eq!(table.permutations(5, 2), 20);
+        assert_eq!(table.factorial(10), 3628800);
+    }
+
+    #[test]
+    fn test_permutations() {
+        let perms = collect(permutations(&['a', 'b', 'c']));
+        assert_eq!(perms.len(), 6);
+        assert_eq!(perms[0], vec!['a', 'b', 'c']);
+        assert_eq!(perms[1], vec!['a', 'c', 'b']);
+        assert_eq!(perms[5], vec!['c', 'b', 'a']);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+        let mut perms = permutations(&[1, 2]);
+        assert_eq!(collect_n(&mut perms, 3), 2);
+        assert_eq!(perms.next(), None);
+
+        for (rank, perm) in collect(permutations(&[0, 1, 2, 3])).iter().enumerate() {
+            assert_eq!(permutation_rank(perm), rank as u128);
+            assert_eq!(&permutation_unrank(&[0, 1, 2, 3], rank as u128), perm);
+        }
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            collect(combinations(&[1, 2, 3, 4], 2)),
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![1, 4],
+                vec![2, 3],
+                vec![2, 4],
+                vec![3, 4]
+            ]
+        );
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+        let mut too_many = combinations(&[1, 2, 3], 4);
+        assert_eq!(too_many.next(), None);
+        assert_eq!(too_many.next(), None);
+        let mut pairs = combinations(&[1, 2], 2);
+        assert_eq!(pairs.next(), Some(&[1, 2][..]));
+        assert_eq!(pairs.next(), None);
+        assert_eq!(pairs.next(), None);
+        assert_eq!(combinations(&[1, 2, 3], 0).count(), 1);
+        assert_eq!(combinations(&[0; 10], 4).count(), 210);
+
+        assert_eq!(
+            collect(combinations_with_replacement(&[1, 2, 3], 2)),
+            vec![
+                vec![1, 1],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 2],
+                vec![2, 3],
+                vec![3, 3]
+            ]
+        );
+        assert_eq!(combinations_with_replacement(&[0; 5], 3).count(), 35);
+    }
+
+    #[test]
+    fn test_cartesian_power() {
+        assert_eq!(
+            collect(cartesian_power(&['+', '*'], 2)),
+            vec![
+                vec!['+', '+'],
+                vec!['+', '*'],
+                vec!['*', '+'],
+                vec!['*', '*']
+            ]
+        );
+        assert_eq!(cartesian_power(&[0, 1, 2], 4).count(), 81);
+        assert_eq!(cartesian_power::<u8>(&[], 2).count(), 0);
+    }
+}
@@ -0,0 +1,246 @@
+use super::grid::{Grid, Point};
+
+/// Collects the coordinates that matter and maps them onto a small dense
+/// grid. With gaps enabled, the space between two consecutive coordinates
+/// gets its own cell so areas on the compressed grid can be turned back into
+/// real areas.
+#[derive(Debug, Clone, Default)]
+pub struct Compressor {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    gaps: bool,
+    border: bool,
+}
+
+impl Compressor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a cell for every run of skipped coordinates.
+    pub fn gaps(mut self, gaps: bool) -> Self {
+        self.gaps = gaps;
+        self
+    }
+
+    /// Surround everything with a one wide ring of extra cells, so a flood
+    /// fill from `(0, 0)` reaches the whole outside.
+    pub fn border(mut self, border: bool) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn add_x(&mut self, x: i64) {
+        self.xs.push(x);
+    }
+
+    pub fn add_y(&mut self, y: i64) {
+        self.ys.push(y);
+    }
+
+    pub fn add_point(&mut self, x: i64, y: i64) {
+        self.add_x(x);
+        self.add_y(y);
+    }
+
+    pub fn build(self) -> Compression {
+        Compression {
+            x: AxisMap::new(self.xs, self.gaps, self.border),
+            y: AxisMap::new(self.ys, self.gaps, self.border),
+        }
+    }
+}
+
+impl Extend<(i64, i64)> for Compressor {
+    fn extend<I: IntoIterator<Item = (i64, i64)>>(&mut self, iter: I) {
+        for (x, y) in iter {
+            self.add_point(x, y);
+        }
+    }
+}
+
+/// One compressed axis: cell `i` covers real coordinates
+/// `starts[i]..starts[i] + widths[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AxisMap {
+    starts: Vec<i64>,
+    widths: Vec<i64>,
+}
+
+impl AxisMap {
+    fn new(mut coords: Vec<i64>, gaps: bool, border: bool) -> Self {
+        coords.sort_unstable();
+        coords.dedup();
+
+        let mut starts = Vec::new();
+        let mut widths = Vec::new();
+        let mut push = |start: i64, width: i64| {
+            starts.push(start);
+            widths.push(width);
+        };
+
+        if border && let Some(&first) = coords.first() {
+            push(first - 1, 1);
+        }
+        for (i, &c) in coords.iter().enumerate() {
+            push(c, 1);
+            if gaps
+                && let Some(&next) = coords.get(i + 1)
+                && next > c + 1
+            {
+                push(c + 1, next - c - 1);
+            }
+        }
+        if border && let Some(&last) = coords.last() {
+            push(last + 1, 1);
+        }
+
+        Self { starts, widths }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// The cell holding the real coordinate `c`, if any.
+    pub fn compress(&self, c: i64) -> Option<usize> {
+        let i = self
+            .starts
+            .partition_point(|&start| start <= c)
+            .checked_sub(1)?;
+        (c < self.starts[i] + self.widths[i]).then_some(i)
+    }
+
+    /// First real coordinate in `cell`.
+    pub fn start(&self, cell: usize) -> i64 {
+        self.starts[cell]
+    }
+
+    /// Number of real coordinates in `cell`.
+    pub fn width(&self, cell: usize) -> i64 {
+        self.widths[cell]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compression {
+    pub x: AxisMap,
+    pub y: AxisMap,
+}
+
+impl Compression {
+    pub fn width(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.y.len()
+    }
+
+    pub fn compress(&self, x: i64, y: i64) -> Option<Point> {
+        Some(Point::new(self.x.compress(x)?, self.y.compress(y)?))
+    }
+
+    /// Real coordinates of the top left of the cell at `point`.
+    pub fn decompress(&self, point: &Point) -> (i64, i64) {
+        (self.x.start(point.x), self.y.start(point.y))
+    }
+
+    /// Number of real cells the compressed cell at `point` stands for.
+    pub fn cell_area(&self, point: &Point) -> i64 {
+        self.x.width(point.x) * self.y.width(point.y)
+    }
+
+    /// A grid of the compressed size filled with `val`.
+    pub fn grid<U: Copy>(&self, val: U) -> Grid<Vec<U>> {
+        Grid::fill(self.width(), self.height(), val)
+    }
+
+    /// Total real area of the compressed cells.
+    pub fn area<I: IntoIterator<Item = Point>>(&self, points: I) -> i64 {
+        points.into_iter().map(|p| self.cell_area(&p)).sum()
+    }
+
+    /// Total real area of the cells in `grid` whose value matches `pred`.
+    pub fn area_where<U, F>(&self, grid: &Grid<Vec<U>>, pred: F) -> i64
+    where
+        F: Fn(&U) -> bool,
+    {
+        self.area(
+            grid.iter()
+                .filter(|cell| pred(&cell.value()))
+                .map(|cell| cell.point()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_axis() {
+        let axis = AxisMap::new(vec![10, 3, 7, 3, 8], true, false);
+        assert_eq!(axis.starts, vec![3, 4, 7, 8, 9, 10]);
+        assert_eq!(axis.widths, vec![1, 3, 1, 1, 1, 1]);
+        assert_eq!(axis.compress(5), Some(1));
+        assert_eq!(axis.compress(2), None);
+        assert_eq!(axis.compress(11), None);
+
+        let plain = AxisMap::new(vec![10, 3, 7], false, true);
+        assert_eq!(plain.starts, vec![2, 3, 7, 10, 11]);
+        assert_eq!(plain.compress(5), None);
+        assert_eq!(plain.compress(7), Some(2));
+    }
+
+    #[test]
+    fn test_flood_fill_area() {
+        // Outline of the tiles from (2, 1) to (1_000_000, 5) inclusive
+        let corners = [(2, 1), (1_000_000, 1), (1_000_000, 5), (2, 5)];
+        let mut compressor = Compressor::new().gaps(true).border(true);
+        compressor.extend(corners);
+        let compression = compressor.build();
+
+        let grid = compression.grid(b'.');
+        for (i, &(x0, y0)) in corners.iter().enumerate() {
+            let (x1, y1) = corners[(i + 1) % corners.len()];
+            let a = compression.compress(x0, y0).unwrap();
+            let b = compression.compress(x1, y1).unwrap();
+            for x in a.x.min(b.x)..=a.x.max(b.x) {
+                for y in a.y.min(b.y)..=a.y.max(b.y) {
+                    *grid.value_at_mut(x, y).unwrap() = b'#';
+                }
+            }
+        }
+
+        let mut stack = vec![Point::new(0, 0)];
+        while let Some(p) = stack.pop() {
+            let Some(mut cell) = p.on(&grid) else {
+                continue;
+            };
+            if *cell.value() != b'.' {
+                continue;
+            }
+            *cell.value_mut() = b'o';
+            stack.extend(
+                [cell.up(), cell.down(), cell.left(), cell.right()]
+                    .into_iter()
+                    .flatten()
+                    .map(|c| c.point()),
+            );
+        }
+
+        assert_eq!(
+            compression.area_where(&grid, |&v| v != b'o'),
+            (1_000_000 - 2 + 1) * 5
+        );
+        assert_eq!(
+            compression.area_where(&grid, |&v| v == b'#'),
+            2 * 999_999 + 2 * 3
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use std::{fmt::Debug, ops::Index};
+
+use super::grid::{Grid, Point};
+
+/// The dimensions of a `DpTable` and how its indices map to storage.
+/// Implemented for `usize` (1D), `(width, height)` indexed by `Point` like a
+/// `Grid`, and `(width, height, depth)` indexed by `(x, y, z)`.
+pub trait Shape: Copy + Debug {
+    type Index: Copy + Debug + PartialEq;
+
+    /// Number of cells.
+    fn size(&self) -> usize;
+
+    /// Storage offset of `index`, `None` if out of bounds.
+    fn offset(&self, index: Self::Index) -> Option<usize>;
+
+    fn index(&self, offset: usize) -> Self::Index;
+}
+
+impl Shape for usize {
+    type Index = usize;
+
+    fn size(&self) -> usize {
+        *self
+    }
+
+    fn offset(&self, index: usize) -> Option<usize> {
+        (index < *self).then_some(index)
+    }
+
+    fn index(&self, offset: usize) -> usize {
+        offset
+    }
+}
+
+impl Shape for (usize, usize) {
+    type Index = Point;
+
+    fn size(&self) -> usize {
+        self.0 * self.1
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        (p.x < self.0 && p.y < self.1).then_some(p.y * self.0 + p.x)
+    }
+
+    fn index(&self, offset: usize) -> Point {
+        Point::new(offset % self.0, offset / self.0)
+    }
+}
+
+impl Shape for (usize, usize, usize) {
+    type Index = (usize, usize, usize);
+
+    fn size(&self) -> usize {
+        self.0 * self.1 * self.2
+    }
+
+    fn offset(&self, (x, y, z): (usize, usize, usize)) -> Option<usize> {
+        (x < self.0 && y < self.1 && z < self.2).then_some((z * self.1 + y) * self.0 + x)
+    }
+
+    fn index(&self, offset: usize) -> (usize, usize, usize) {
+        let (x, rest) = (offset % self.0, offset / self.0);
+        (x, rest % self.1, rest / self.1)
+    }
+}
+
+/// A top-down evaluation that depended on itself, with the chain of
+/// indices from the first repeated one back round to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<I>(pub Vec<I>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    InProgress,
+    Done,
+}
+
+/// Table of sub-problem results, either filled bottom-up in a given order
+/// or evaluated lazily top-down.
+#[derive(Debug, Clone)]
+pub struct DpTable<S: Shape, T> {
+    shape: S,
+    values: Vec<Option<T>>,
+}
+
+impl<S: Shape, T> DpTable<S, T> {
+    pub fn new(shape: S) -> Self {
+        Self {
+            shape,
+            values: std::iter::repeat_with(|| None).take(shape.size()).collect(),
+        }
+    }
+
+    pub fn shape(&self) -> S {
+        self.shape
+    }
+
+    /// The value at `index` if it is in bounds and has been computed.
+    pub fn get(&self, index: S::Index) -> Option<&T> {
+        self.values[self.shape.offset(index)?].as_ref()
+    }
+
+    pub fn set(&mut self, index: S::Index, value: T) {
+        let offset = self.offset(index);
+        self.values[offset] = Some(value);
+    }
+
+    fn offset(&self, index: S::Index) -> usize {
+        self.shape
+            .offset(index)
+            .unwrap_or_else(|| panic!("{index:?} is outside {:?}", self.shape))
+    }
+
+    /// Compute every index in `order`, which must list each index after the
+    /// ones it depends on. `f` reads those through the table.
+    pub fn fill_in<I, F>(&mut self, order: I, mut f: F)
+    where
+        I: IntoIterator<Item = S::Index>,
+        F: FnMut(&Self, S::Index) -> T,
+    {
+        for index in order {
+            let value = f(self, index);
+            self.set(index, value);
+        }
+    }
+
+    /// Compute every index in storage order: x fastest, then y, then z.
+    pub fn fill<F>(&mut self, f: F)
+    where
+        F: FnMut(&Self, S::Index) -> T,
+    {
+        let shape = self.shape;
+        self.fill_in((0..shape.size()).map(|i| shape.index(i)), f);
+    }
+}
+
+impl<S: Shape, T: Clone> DpTable<S, T> {
+    /// Evaluate `index` top-down, computing only what it depends on. `f`
+    /// gets a function to look up other indices through, and any dependency
+    /// on an index still being evaluated is reported as a `Cycle`.
+    ///
+    /// Dependencies go on an explicit stack rather than the call stack, so
+    /// long chains are fine. When `f` asks for an index that hasn't been
+    /// computed yet it is abandoned and run again once that index is done.
+    pub fn solve<F>(&mut self, index: S::Index, f: F) -> Result<T, Cycle<S::Index>>
+    where
+        F: Fn(
+            &mut dyn FnMut(S::Index) -> Result<T, Cycle<S::Index>>,
+            S::Index,
+        ) -> Result<T, Cycle<S::Index>>,
+    {
+        let mut status: Vec<Status> = self
+            .values
+            .iter()
+            .map(|v| {
+                if v.is_some() {
+                    Status::Done
+                } else {
+                    Status::Pending
+                }
+            })
+            .collect();
+        let root = self.offset(index);
+        if let Some(value) = &self.values[root] {
+            return Ok(value.clone());
+        }
+        status[root] = Status::InProgress;
+        let mut stack = vec![index];
+
+        while let Some(&top) = stack.last() {
+            // The first dependency that still needs computing, reported to
+            // `f` as an empty cycle so it bails out through `?`
+            let mut missing = None;
+            let result = f(
+                &mut |i| {
+                    let offset = self.offset(i);
+                    match status[offset] {
+                        Status::Done => Ok(self.values[offset].clone().unwrap()),
+                        Status::InProgress => {
+                            let start = stack.iter().position(|&j| j == i).unwrap();
+                            Err(Cycle(stack[start..].to_vec()))
+                        }
+                        Status::Pending => {
+                            missing.get_or_insert((i, offset));
+                            Err(Cycle(Vec::new()))
+                        }
+                    }
+                },
+                top,
+            );
+            match (result, missing) {
+                (Err(cycle), _) if !cycle.0.is_empty() => return Err(cycle),
+                (_, Some((i, offset))) => {
+                    status[offset] = Status::InProgress;
+                    stack.push(i);
+                }
+                (value, None) => {
+                    let offset = self.offset(top);
+                    status[offset] = Status::Done;
+                    self.values[offset] = Some(value.unwrap_or_else(|_| unreachable!()));
+                    stack.pop();
+                }
+            }
+        }
+        Ok(self.values[root].clone().unwrap())
+    }
+}
+
+impl<T> DpTable<(usize, usize), T> {
+    /// A 2D table the size of `grid`.
+    pub fn for_grid<U: Index<usize>>(grid: &Grid<U>) -> Self {
+        Self::new((grid.width(), grid.height()))
+    }
+}
+
+impl<S: Shape, T> Index<S::Index> for DpTable<S, T> {
+    type Output = T;
+
+    /// Panics if `index` hasn't been computed.
+    fn index(&self, index: S::Index) -> &T {
+        self.values[self.offset(index)]
+            .as_ref()
+            .unwrap_or_else(|| panic!("{index:?} has not been computed"))
+    }
+}
+
+/// Row by row DP keeping only the previous row. `step` fills the current
+/// row from the previous one, given the row number starting from 1, and the
+/// last row is returned.
+pub fn rolling_rows<T, F>(first: Vec<T>, rows: usize, mut step: F) -> Vec<T>
+where
+    T: Clone,
+    F: FnMut(&[T], &mut [T], usize),
+{
+    let mut previous = first;
+    let mut current = previous.clone();
+    for y in 1..rows {
+        step(&previous, &mut current, y);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bottom_up() {
+        // Longest common subsequence
+        let (a, b) = (b"ABCBDAB", b"BDCABA");
+        let mut table: DpTable<_, usize> = DpTable::new((a.len() + 1, b.len() + 1));
+        table.fill(|t, p| {
+            if p.x == 0 || p.y == 0 {
+                0
+            } else if a[p.x - 1] == b[p.y - 1] {
+                t[Point::new(p.x - 1, p.y - 1)] + 1
+            } else {
+                t[Point::new(p.x - 1, p.y)].max(t[Point::new(p.x, p.y - 1)])
+            }
+        });
+        assert_eq!(table[Point::new(a.len(), b.len())], 4);
+
+        // Explicit order: 1D from the end backwards
+        let mut jumps = DpTable::new(10);
+        jumps.fill_in((0..10).rev(), |t, i| {
+            1 + t.get(i + 1).copied().unwrap_or(0) + t.get(i + 2).copied().unwrap_or(0)
+        });
+        assert_eq!(jumps[8], 2);
+        assert_eq!(jumps[0], 143);
+    }
+
+    #[test]
+    fn test_three_dimensions() {
+        // Ways to reach (x, y, z) with unit steps
+        let mut table = DpTable::new((4, 4, 4));
+        table.fill(|t, (x, y, z)| {
+            if x + y + z == 0 {
+                return 1u64;
+            }
+            let from =
+                |d: Option<(usize, usize, usize)>| d.and_then(|i| t.get(i)).copied().unwrap_or(0);
+            from(x.checked_sub(1).map(|x| (x, y, z)))
+                + from(y.checked_sub(1).map(|y| (x, y, z)))
+                + from(z.checked_sub(1).map(|z| (x, y, z)))
+        });
+        // 9! / (3! 3! 3!)
+        assert_eq!(table[(3, 3, 3)], 1680);
+        assert_eq!(table.shape().index(63), (3, 3, 3));
+    }
+
+    #[test]
+    fn test_top_down() {
+        let grid: Grid<Vec<u8>> = "131\n512\n421".parse().unwrap();
+        let mut table = DpTable::for_grid(&grid);
+        // Cheapest path to the bottom right moving right or down
+        let cost = table.solve(Point::new(0, 0), |next, p| {
+            let here = *grid.value_at(p.x, p.y).unwrap() as u32;
+            let moves = [p.right(), p.down()]
+                .into_iter()
+                .filter(|q| grid.in_bounds_point(q));
+            let best = moves.map(&mut *next).collect::<Result<Vec<_>, _>>()?;
+            Ok(here + best.into_iter().min().unwrap_or(0))
+        });
+        assert_eq!(cost, Ok(8));
+        assert_eq!(table.get(Point::new(2, 0)), Some(&4));
+
+        let mut cyclic: DpTable<usize, u32> = DpTable::new(5);
+        let result = cyclic.solve(0, |next, i| Ok(next((i + 1) % 3)? + 1));
+        assert_eq!(result, Err(Cycle(vec![0, 1, 2])));
+    }
+
+    #[test]
+    fn test_top_down_long_chain() {
+        // Each index needs the next, far deeper than the call stack allows
+        let n = 200_000;
+        let mut table: DpTable<usize, u64> = DpTable::new(n);
+        let sum = table.solve(0, |next, i| {
+            Ok(i as u64 + if i + 1 < n { next(i + 1)? } else { 0 })
+        });
+        assert_eq!(sum, Ok((n as u64 - 1) * n as u64 / 2));
+        assert_eq!(table.get(n - 1), Some(&(n as u64 - 1)));
+    }
+
+    #[test]
+    fn test_rolling_rows() {
+        // Pascal's triangle row 20, padded to a fixed width
+        let mut first = vec![0u64; 21];
+        first[0] = 1;
+        let row = rolling_rows(first, 21, |previous, current, _| {
+            current[0] = 1;
+            for x in 1..current.len() {
+                current[x] = previous[x - 1] + previous[x];
+            }
+        });
+        assert_eq!(row[10], 184756);
+        assert_eq!(row[20], 1);
+    }
+}
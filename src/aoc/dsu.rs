@@ -0,0 +1,369 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::grid::Point;
+
+/// Disjoint set union over `0..n` with path compression and union by size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Add a new singleton set, returning its index.
+    pub fn push(&mut self) -> usize {
+        self.parent.push(self.parent.len());
+        self.size.push(1);
+        self.components += 1;
+        self.parent.len() - 1
+    }
+
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merge the sets holding `a` and `b`, returning false if they were
+    /// already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set holding `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of disjoint sets.
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// The size of every set, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|x| self.size[x])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// Members of the set holding `x`, in increasing order.
+    pub fn members(&mut self, x: usize) -> Vec<usize> {
+        let root = self.find(x);
+        (0..self.len()).filter(|&y| self.find(y) == root).collect()
+    }
+
+    /// Every set's members in increasing order, sets ordered by their
+    /// smallest member.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut slot = vec![usize::MAX; self.len()];
+        let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            if slot[root] == usize::MAX {
+                slot[root] = groups.len();
+                groups.push(Vec::new());
+            }
+            groups[slot[root]].push(x);
+        }
+        groups
+    }
+}
+
+/// Union by size without path compression, so every union can be undone in
+/// reverse order. `find` is O(log n).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollbackDsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    components: usize,
+    // The root attached by each union, or None for unions that did nothing
+    history: Vec<Option<usize>>,
+}
+
+impl RollbackDsu {
+    pub fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            components: n,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            self.history.push(None);
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        self.history.push(Some(b));
+        true
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+
+    /// A marker to pass to `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undo the most recent union.
+    pub fn undo(&mut self) {
+        if let Some(Some(b)) = self.history.pop() {
+            let a = self.parent[b];
+            self.parent[b] = b;
+            self.size[a] -= self.size[b];
+            self.components += 1;
+        }
+    }
+
+    /// Undo every union since `snapshot` was taken.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+/// A `Dsu` over arbitrary keys, interned to dense indices as they are first
+/// seen.
+#[derive(Debug, Clone)]
+pub struct KeyedDsu<K> {
+    dsu: Dsu,
+    index: HashMap<K, usize>,
+    keys: Vec<K>,
+}
+
+pub type PointDsu = KeyedDsu<Point>;
+
+impl<K> Default for KeyedDsu<K> {
+    fn default() -> Self {
+        Self {
+            dsu: Dsu::new(0),
+            index: HashMap::new(),
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone> KeyedDsu<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The dense index of `key`, adding it as a singleton set if new.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&i) = self.index.get(&key) {
+            return i;
+        }
+        let i = self.dsu.push();
+        self.index.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub fn index_of(&self, key: &K) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    pub fn key(&self, index: usize) -> &K {
+        &self.keys[index]
+    }
+
+    /// The underlying dense structure, for mixing in index based calls.
+    pub fn dsu(&mut self) -> &mut Dsu {
+        &mut self.dsu
+    }
+
+    /// Merge the sets holding `a` and `b`, adding either if new.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.dsu.union(a, b)
+    }
+
+    /// The representative key of the set holding `key`.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let i = self.index_of(key)?;
+        let root = self.dsu.find(i);
+        Some(&self.keys[root])
+    }
+
+    /// False if either key was never added.
+    pub fn same(&mut self, a: &K, b: &K) -> bool {
+        match (self.index_of(a), self.index_of(b)) {
+            (Some(a), Some(b)) => self.dsu.same(a, b),
+            _ => false,
+        }
+    }
+
+    pub fn size(&mut self, key: &K) -> Option<usize> {
+        let i = self.index_of(key)?;
+        Some(self.dsu.size(i))
+    }
+
+    pub fn components(&self) -> usize {
+        self.dsu.components()
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.dsu.component_sizes()
+    }
+
+    /// Every set's keys in insertion order, sets ordered by their first key.
+    pub fn groups(&mut self) -> Vec<Vec<K>> {
+        self.dsu
+            .groups()
+            .into_iter()
+            .map(|group| group.into_iter().map(|i| self.keys[i].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dsu() {
+        let mut dsu = Dsu::new(8);
+        assert!(dsu.union(0, 1));
+        assert!(dsu.union(2, 3));
+        assert!(dsu.union(1, 3));
+        assert!(!dsu.union(0, 2));
+        assert!(dsu.union(5, 6));
+
+        assert_eq!(dsu.components(), 4);
+        assert!(dsu.same(0, 3));
+        assert!(!dsu.same(0, 5));
+        assert_eq!(dsu.size(2), 4);
+        assert_eq!(dsu.component_sizes(), vec![4, 2, 1, 1]);
+        assert_eq!(dsu.members(6), vec![5, 6]);
+        assert_eq!(
+            dsu.groups(),
+            vec![vec![0, 1, 2, 3], vec![4], vec![5, 6], vec![7]]
+        );
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut dsu = RollbackDsu::new(5);
+        dsu.union(0, 1);
+        let snapshot = dsu.snapshot();
+        dsu.union(1, 2);
+        dsu.union(0, 2);
+        dsu.union(3, 4);
+        assert_eq!(dsu.components(), 2);
+        assert_eq!(dsu.size(0), 3);
+
+        dsu.rollback(snapshot);
+        assert_eq!(dsu.components(), 4);
+        assert!(dsu.same(0, 1));
+        assert!(!dsu.same(1, 2));
+        assert!(!dsu.same(3, 4));
+        assert_eq!(dsu.size(0), 2);
+    }
+
+    #[test]
+    fn test_points() {
+        let mut dsu = PointDsu::new();
+        dsu.union(Point::new(0, 0), Point::new(0, 1));
+        dsu.union(Point::new(5, 5), Point::new(5, 6));
+        dsu.union(Point::new(0, 1), Point::new(1, 1));
+        dsu.insert(Point::new(9, 9));
+
+        assert_eq!(dsu.len(), 6);
+        assert_eq!(dsu.components(), 3);
+        assert!(dsu.same(&Point::new(0, 0), &Point::new(1, 1)));
+        assert!(!dsu.same(&Point::new(0, 0), &Point::new(3, 3)));
+        assert_eq!(dsu.size(&Point::new(1, 1)), Some(3));
+        assert_eq!(
+            dsu.groups(),
+            vec![
+                vec![Point::new(0, 0), Point::new(0, 1), Point::new(1, 1)],
+                vec![Point::new(5, 5), Point::new(5, 6)],
+                vec![Point::new(9, 9)],
+            ]
+        );
+    }
+}
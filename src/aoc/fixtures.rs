@@ -0,0 +1,37 @@
+//! Inputs shared between test modules.
+
+/// A small maze of corridors with one loop, entered at the top and left at
+/// the bottom.
+pub const MAZE: &str = "\
+#.#####
+#.....#
+#.###.#
+#.....#
+#####.#";
+
+/// Deterministic pseudo-random numbers from a 64-bit linear congruential
+/// generator, so tests can use random inputs without a dependency.
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Uniform enough in `0..m` for small `m`.
+    pub fn below(&mut self, m: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % m
+    }
+}
+
+/// `n` points with coordinates in `0..spread`.
+pub fn scatter<const D: usize>(n: usize, spread: i64) -> Vec<[i64; D]> {
+    let mut rng = Lcg::new(0x2545f4914f6cdd1d);
+    (0..n)
+        .map(|_| std::array::from_fn(|_| rng.below(spread as u64) as i64))
+        .collect()
+}
@@ -0,0 +1,12 @@
+mod closest;
+mod lines;
+mod polygon;
+mod vector;
+
+pub use closest::closest_pair;
+pub use lines::{
+    Exact, LineIntersection, Orientation, SegmentIntersection, cross, intersection_params,
+    line_intersection, on_segment, orientation, segment_intersection,
+};
+pub use polygon::{Containment, area2, convex_hull, point_in_polygon};
+pub use vector::{Vec2, Vec3};
@@ -0,0 +1,148 @@
+use super::Vec2;
+
+// Keeps each squared difference below 2^126, so their sum fits an i128
+const LIMIT: i64 = 1 << 62;
+
+fn dist2(a: Vec2<i64>, b: Vec2<i64>) -> i128 {
+    let (a, b) = (a.map(|c| c as i128), b.map(|c| c as i128));
+    a.distance_squared(&b)
+}
+
+/// The closest pair of points as `(i, j, squared distance)` with `i < j`
+/// indexing into `points`, by divide and conquer in O(n log n). Ties go to
+/// the pair with the smallest indices. Panics if a coordinate is outside
+/// `±2^62`, as squared distances beyond that could overflow the `i128`.
+pub fn closest_pair(points: &[Vec2<i64>]) -> Option<(usize, usize, i128)> {
+    assert!(
+        points
+            .iter()
+            .all(|p| (-LIMIT < p.x && p.x < LIMIT) && (-LIMIT < p.y && p.y < LIMIT)),
+        "closest_pair needs coordinates strictly within ±2^62"
+    );
+    if points.len() < 2 {
+        return None;
+    }
+    let mut indexed: Vec<(Vec2<i64>, usize)> = points.iter().copied().zip(0..).collect();
+    indexed.sort_unstable_by_key(|&(p, i)| (p.x, p.y, i));
+    let mut scratch = indexed.clone();
+    let (i, j, d) = solve(&mut indexed, &mut scratch);
+    Some((i.min(j), i.max(j), d))
+}
+
+type Best = (usize, usize, i128);
+
+fn better(candidate: Best, best: Best) -> bool {
+    let key = |(i, j, d): Best| (d, i.min(j), i.max(j));
+    key(candidate) < key(best)
+}
+
+// Sorted by x on entry, sorted by y on exit
+fn solve(pts: &mut [(Vec2<i64>, usize)], scratch: &mut [(Vec2<i64>, usize)]) -> Best {
+    let n = pts.len();
+    if n <= 3 {
+        let mut best = (0, 0, i128::MAX);
+        for a in 0..n {
+            for b in a + 1..n {
+                let candidate = (pts[a].1, pts[b].1, dist2(pts[a].0, pts[b].0));
+                if better(candidate, best) {
+                    best = candidate;
+                }
+            }
+        }
+        pts.sort_unstable_by_key(|&(p, i)| (p.y, p.x, i));
+        return best;
+    }
+
+    let mid = n / 2;
+    let split_x = pts[mid].0.x as i128;
+    let (left, right) = pts.split_at_mut(mid);
+    let (scratch_left, scratch_right) = scratch.split_at_mut(mid);
+    let a = solve(left, scratch_left);
+    let b = solve(right, scratch_right);
+    let mut best = if better(b, a) { b } else { a };
+
+    // Merge the halves back by y
+    let (mut i, mut j) = (0, mid);
+    for slot in scratch.iter_mut() {
+        let take_left = j == n || (i < mid && (pts[i].0.y, pts[i].0.x) <= (pts[j].0.y, pts[j].0.x));
+        if take_left {
+            *slot = pts[i];
+            i += 1;
+        } else {
+            *slot = pts[j];
+            j += 1;
+        }
+    }
+    pts.copy_from_slice(scratch);
+
+    // Only points within the current best of the split line can do better,
+    // and each only needs comparing with the few above it in the strip
+    let strip: Vec<(Vec2<i64>, usize)> = pts
+        .iter()
+        .copied()
+        .filter(|(p, _)| (p.x as i128 - split_x).pow(2) <= best.2)
+        .collect();
+    for a in 0..strip.len() {
+        for b in a + 1..strip.len() {
+            let dy = strip[b].0.y as i128 - strip[a].0.y as i128;
+            if dy * dy > best.2 {
+                break;
+            }
+            let candidate = (strip[a].1, strip[b].1, dist2(strip[a].0, strip[b].0));
+            if better(candidate, best) {
+                best = candidate;
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::fixtures::scatter;
+
+    fn brute(points: &[Vec2<i64>]) -> Option<(usize, usize, i128)> {
+        let mut best = None;
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let d = dist2(points[i], points[j]);
+                if best.is_none_or(|(_, _, bd)| d < bd) {
+                    best = Some((i, j, d));
+                }
+            }
+        }
+        best
+    }
+
+    #[test]
+    fn test_closest_pair() {
+        assert_eq!(closest_pair(&[Vec2::new(0, 0)]), None);
+
+        let points: Vec<Vec2<i64>> = scatter::<2>(500, 10_000)
+            .into_iter()
+            .map(|[x, y]| Vec2::new(x - 5_000, y - 5_000))
+            .collect();
+        assert_eq!(closest_pair(&points), brute(&points));
+
+        let ties = [
+            Vec2::new(0, 0),
+            Vec2::new(10, 0),
+            Vec2::new(11, 0),
+            Vec2::new(1, 0),
+        ];
+        assert_eq!(closest_pair(&ties), Some((0, 3, 1)));
+
+        // Right at the limit the distance still fits
+        let edge = LIMIT - 1;
+        let corners = [Vec2::new(-edge, -edge), Vec2::new(edge, edge)];
+        let side = 2 * edge as i128;
+        assert_eq!(closest_pair(&corners), Some((0, 1, 2 * side * side)));
+    }
+
+    #[test]
+    #[should_panic(expected = "within ±2^62")]
+    fn test_closest_pair_out_of_range() {
+        closest_pair(&[Vec2::new(i64::MIN, 0), Vec2::new(i64::MAX, 0)]);
+    }
+}
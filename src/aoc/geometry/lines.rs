@@ -0,0 +1,367 @@
+use std::cmp::Ordering;
+
+use super::Vec2;
+use crate::quant::{BigInt, Rational};
+
+/// Exact coordinates and line parameters. Built on `BigInt` because the
+/// crossing of lines through arbitrary `i64` points can need numerators far
+/// past `i128`.
+pub type Exact = Rational<BigInt>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Collinear,
+}
+
+fn wide(v: Vec2<i64>) -> Vec2<i128> {
+    v.map(|c| c as i128)
+}
+
+/// Twice the signed area of the triangle `abc`, positive when the points turn
+/// anticlockwise (with y pointing up). `None` if that doesn't fit an `i128`,
+/// which takes coordinates beyond `±2^62`; use `orientation` when only the
+/// sign is needed.
+pub fn cross(a: Vec2<i64>, b: Vec2<i64>, c: Vec2<i64>) -> Option<i128> {
+    let (u, v) = (wide(b) - wide(a), wide(c) - wide(a));
+    u.x.checked_mul(v.y)?.checked_sub(u.y.checked_mul(v.x)?)
+}
+
+// Sign and magnitude of `a * b` for factors below 2^64 in size, which always
+// fits a u128
+fn product(a: i128, b: i128) -> (Ordering, u128) {
+    (
+        (a.signum() * b.signum()).cmp(&0),
+        a.unsigned_abs() * b.unsigned_abs(),
+    )
+}
+
+// Sign of `u.cross(v)` for differences of i64 coordinates, without forming
+// the cross product itself
+fn cross_sign(u: Vec2<i128>, v: Vec2<i128>) -> Ordering {
+    let (lhs, rhs) = (product(u.x, v.y), product(u.y, v.x));
+    match (lhs.0, rhs.0) {
+        (Ordering::Greater, Ordering::Greater) => lhs.1.cmp(&rhs.1),
+        (Ordering::Less, Ordering::Less) => rhs.1.cmp(&lhs.1),
+        (l, r) => l.cmp(&r),
+    }
+}
+
+/// Exact for every `i64` input.
+pub fn orientation(a: Vec2<i64>, b: Vec2<i64>, c: Vec2<i64>) -> Orientation {
+    let (a, b, c) = (wide(a), wide(b), wide(c));
+    match cross_sign(b - a, c - a) {
+        Ordering::Greater => Orientation::CounterClockwise,
+        Ordering::Less => Orientation::Clockwise,
+        Ordering::Equal => Orientation::Collinear,
+    }
+}
+
+/// Whether `p` lies on the closed segment `a b`, which may be a single point.
+pub fn on_segment(a: Vec2<i64>, b: Vec2<i64>, p: Vec2<i64>) -> bool {
+    orientation(a, b, p) == Orientation::Collinear
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineIntersection {
+    Parallel,
+    Coincident,
+    Point(Vec2<Exact>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentIntersection {
+    None,
+    Point(Vec2<Exact>),
+    /// Collinear segments sharing the stretch between these two endpoints.
+    Overlap(Vec2<i64>, Vec2<i64>),
+}
+
+fn big_cross(u: Vec2<i128>, v: Vec2<i128>) -> BigInt {
+    BigInt::from(u.x) * BigInt::from(v.y) - BigInt::from(u.y) * BigInt::from(v.x)
+}
+
+fn exact(p: Vec2<i64>) -> Vec2<Exact> {
+    Vec2::new(
+        Exact::from_integer(p.x.into()),
+        Exact::from_integer(p.y.into()),
+    )
+}
+
+/// Where along each line the infinite lines `p1 p2` and `p3 p4` cross, as
+/// `(t, u)` with the crossing at `p1 + t (p2 - p1) = p3 + u (p4 - p3)`.
+/// `None` for parallel lines. Exact for every `i64` input.
+pub fn intersection_params(
+    p1: Vec2<i64>,
+    p2: Vec2<i64>,
+    p3: Vec2<i64>,
+    p4: Vec2<i64>,
+) -> Option<(Exact, Exact)> {
+    let (p1, p2, p3, p4) = (wide(p1), wide(p2), wide(p3), wide(p4));
+    let (r, s) = (p2 - p1, p4 - p3);
+    if cross_sign(r, s) == Ordering::Equal {
+        return None;
+    }
+    let denom = big_cross(r, s);
+    let q = p3 - p1;
+    Some((
+        Exact::new(big_cross(q, s), denom.clone()),
+        Exact::new(big_cross(q, r), denom),
+    ))
+}
+
+fn point_at(p1: Vec2<i64>, p2: Vec2<i64>, t: Exact) -> Vec2<Exact> {
+    let along = |a: i64, b: i64| {
+        let step = Exact::from_integer(BigInt::from(b as i128 - a as i128));
+        Exact::from_integer(a.into()) + step * t.clone()
+    };
+    Vec2::new(along(p1.x, p2.x), along(p1.y, p2.y))
+}
+
+/// Intersection of the infinite lines through `p1 p2` and `p3 p4`. Exact for
+/// every `i64` input.
+pub fn line_intersection(
+    p1: Vec2<i64>,
+    p2: Vec2<i64>,
+    p3: Vec2<i64>,
+    p4: Vec2<i64>,
+) -> LineIntersection {
+    match intersection_params(p1, p2, p3, p4) {
+        Some((t, _)) => LineIntersection::Point(point_at(p1, p2, t)),
+        None if orientation(p1, p2, p3) == Orientation::Collinear => LineIntersection::Coincident,
+        None => LineIntersection::Parallel,
+    }
+}
+
+/// Intersection of the closed segments `p1 p2` and `p3 p4`, either of which
+/// may be a single point. Exact for every `i64` input.
+pub fn segment_intersection(
+    p1: Vec2<i64>,
+    p2: Vec2<i64>,
+    p3: Vec2<i64>,
+    p4: Vec2<i64>,
+) -> SegmentIntersection {
+    use Orientation::Collinear;
+
+    let at = |p: Vec2<i64>| SegmentIntersection::Point(exact(p));
+    // A point is collinear with everything, so test it against the other
+    // segment directly
+    if p1 == p2 || p3 == p4 {
+        let (point, a, b) = if p1 == p2 { (p1, p3, p4) } else { (p3, p1, p2) };
+        return if on_segment(a, b, point) {
+            at(point)
+        } else {
+            SegmentIntersection::None
+        };
+    }
+
+    let (o1, o2) = (orientation(p1, p2, p3), orientation(p1, p2, p4));
+    let (o3, o4) = (orientation(p3, p4, p1), orientation(p3, p4, p2));
+    match (o1, o2, o3, o4) {
+        (Collinear, Collinear, _, _) => collinear_overlap(p1, p2, p3, p4),
+        _ if o1 == o2 || o3 == o4 => SegmentIntersection::None,
+        // One segment ends on the other
+        (Collinear, ..) => at(p3),
+        (_, Collinear, ..) => at(p4),
+        (_, _, Collinear, _) => at(p1),
+        (.., Collinear) => at(p2),
+        _ => {
+            let (t, _) = intersection_params(p1, p2, p3, p4).unwrap();
+            SegmentIntersection::Point(point_at(p1, p2, t))
+        }
+    }
+}
+
+fn collinear_overlap(
+    p1: Vec2<i64>,
+    p2: Vec2<i64>,
+    p3: Vec2<i64>,
+    p4: Vec2<i64>,
+) -> SegmentIntersection {
+    // Order everything along the segment's main axis
+    let key = |p: Vec2<i64>| if p1.x != p2.x { (p.x, p.y) } else { (p.y, p.x) };
+    let (a0, a1) = if key(p1) <= key(p2) {
+        (p1, p2)
+    } else {
+        (p2, p1)
+    };
+    let (b0, b1) = if key(p3) <= key(p4) {
+        (p3, p4)
+    } else {
+        (p4, p3)
+    };
+    let start = if key(a0) >= key(b0) { a0 } else { b0 };
+    let end = if key(a1) <= key(b1) { a1 } else { b1 };
+    match key(start).cmp(&key(end)) {
+        Ordering::Greater => SegmentIntersection::None,
+        Ordering::Equal => SegmentIntersection::Point(exact(start)),
+        Ordering::Less => SegmentIntersection::Overlap(start, end),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn v(x: i64, y: i64) -> Vec2<i64> {
+        Vec2::new(x, y)
+    }
+
+    fn r(num: i128, den: i128) -> Exact {
+        Exact::new(num.into(), den.into())
+    }
+
+    #[test]
+    fn test_orientation() {
+        assert_eq!(
+            orientation(v(0, 0), v(1, 0), v(1, 1)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(v(0, 0), v(1, 0), v(1, -1)),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            orientation(v(0, 0), v(1, 1), v(2, 2)),
+            Orientation::Collinear
+        );
+        // Would overflow in i64
+        let big = i64::MAX / 2;
+        assert_eq!(
+            orientation(v(-big, -big), v(big, -big), v(big, big)),
+            Orientation::CounterClockwise
+        );
+        // Would overflow even in i128
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        assert_eq!(
+            orientation(v(lo, lo), v(hi, lo), v(hi, hi)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(
+            orientation(v(lo, lo), v(hi, hi), v(hi, lo)),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            orientation(v(lo, lo), v(0, 0), v(hi - 1, hi - 1)),
+            Orientation::Collinear
+        );
+    }
+
+    #[test]
+    fn test_cross() {
+        assert_eq!(cross(v(0, 0), v(4, 0), v(0, 3)), Some(12));
+        let big = (1 << 62) - 1;
+        assert_eq!(
+            cross(v(-big, -big), v(big, -big), v(-big, big)),
+            Some(4 * (big as i128).pow(2))
+        );
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        assert_eq!(cross(v(lo, lo), v(hi, lo), v(hi, hi)), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            line_intersection(v(0, 0), v(2, 1), v(0, 1), v(1, 0)),
+            LineIntersection::Point(Vec2::new(r(2, 3), r(1, 3)))
+        );
+        assert_eq!(
+            line_intersection(v(0, 0), v(1, 1), v(0, 1), v(1, 2)),
+            LineIntersection::Parallel
+        );
+        assert_eq!(
+            line_intersection(v(0, 0), v(1, 1), v(5, 5), v(7, 7)),
+            LineIntersection::Coincident
+        );
+
+        // Hailstones A and E from the classic example, whose paths crossed
+        // in A's past
+        let (t, u) = intersection_params(v(19, 13), v(17, 14), v(20, 19), v(21, 14)).unwrap();
+        assert_eq!(t, r(-11, 9));
+        assert_eq!(u, r(13, 9));
+
+        // Full i64 range: the diagonals of the whole plane cross near the
+        // origin
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        let LineIntersection::Point(p) =
+            line_intersection(v(lo, lo), v(hi, hi), v(lo, hi), v(hi, lo))
+        else {
+            panic!("diagonals must cross");
+        };
+        assert_eq!(p, Vec2::new(r(-1, 2), r(-1, 2)));
+        assert_eq!(
+            line_intersection(v(lo, lo), v(hi, lo), v(lo, hi), v(hi, hi)),
+            LineIntersection::Parallel
+        );
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(
+            segment_intersection(v(0, 0), v(4, 4), v(0, 4), v(4, 0)),
+            SegmentIntersection::Point(Vec2::new(r(2, 1), r(2, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(1, 1), v(0, 4), v(4, 0)),
+            SegmentIntersection::None
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(4, 0), v(6, 0), v(2, 0)),
+            SegmentIntersection::Overlap(v(2, 0), v(4, 0))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(0, 4), v(0, 4), v(0, 9)),
+            SegmentIntersection::Point(Vec2::new(r(0, 1), r(4, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(0, 0), v(0, 4), v(0, 5), v(0, 9)),
+            SegmentIntersection::None
+        );
+
+        // Full i64 range, where no intermediate product fits an i128
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        assert_eq!(
+            segment_intersection(v(lo, lo), v(hi, lo), v(hi, lo), v(hi, hi)),
+            SegmentIntersection::Point(Vec2::new(r(hi as i128, 1), r(lo as i128, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(lo, lo), v(hi, hi), v(lo, hi), v(lo + 1, hi)),
+            SegmentIntersection::None
+        );
+        assert_eq!(
+            segment_intersection(v(lo, 0), v(hi, 0), v(0, 0), v(hi, 0)),
+            SegmentIntersection::Overlap(v(0, 0), v(hi, 0))
+        );
+        assert_eq!(
+            segment_intersection(v(lo, lo), v(hi, hi), v(0, 0), v(hi, lo)),
+            SegmentIntersection::Point(Vec2::new(r(0, 1), r(0, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(lo, lo), v(hi, hi), v(lo, hi), v(hi, lo)),
+            SegmentIntersection::Point(Vec2::new(r(-1, 2), r(-1, 2)))
+        );
+
+        // Single points only meet segments they lie on
+        assert_eq!(
+            segment_intersection(v(0, 0), v(0, 0), v(1, -1), v(1, 1)),
+            SegmentIntersection::None
+        );
+        assert_eq!(
+            segment_intersection(v(1, 0), v(1, 0), v(1, -1), v(1, 1)),
+            SegmentIntersection::Point(Vec2::new(r(1, 1), r(0, 1)))
+        );
+        assert_eq!(
+            segment_intersection(v(1, -1), v(1, 1), v(1, 2), v(1, 2)),
+            SegmentIntersection::None
+        );
+        assert_eq!(
+            segment_intersection(v(3, 3), v(3, 3), v(3, 3), v(3, 3)),
+            SegmentIntersection::Point(Vec2::new(r(3, 1), r(3, 1)))
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use super::{
+    Vec2,
+    lines::{Orientation, on_segment, orientation},
+};
+
+/// Convex hull by Andrew's monotone chain, anticlockwise (with y pointing up)
+/// starting from the lowest x, without collinear points.
+pub fn convex_hull(points: &[Vec2<i64>]) -> Vec<Vec2<i64>> {
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let mut hull: Vec<Vec2<i64>> = Vec::with_capacity(sorted.len() + 1);
+    let push = |hull: &mut Vec<Vec2<i64>>, floor: usize, p: Vec2<i64>| {
+        while hull.len() >= floor + 2
+            && orientation(hull[hull.len() - 2], hull[hull.len() - 1], p)
+                != Orientation::CounterClockwise
+        {
+            hull.pop();
+        }
+        hull.push(p);
+    };
+    for &p in &sorted {
+        push(&mut hull, 0, p);
+    }
+    // The upper chain starts from the lower chain's last point
+    let floor = hull.len() - 1;
+    for &p in sorted.iter().rev().skip(1) {
+        push(&mut hull, floor, p);
+    }
+    // The last point added is the first point again
+    hull.pop();
+    hull
+}
+
+/// Twice the signed area of the polygon, positive for anticlockwise vertices.
+pub fn area2(polygon: &[Vec2<i64>]) -> i128 {
+    (0..polygon.len())
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128
+        })
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Containment {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+/// Where `p` lies relative to a simple polygon given by its vertices in
+/// either winding order, by counting crossings of a ray towards +x.
+pub fn point_in_polygon(polygon: &[Vec2<i64>], p: Vec2<i64>) -> Containment {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+        if on_segment(a, b, p) {
+            return Containment::Boundary;
+        }
+        // Half-open in y so a vertex on the ray only counts once
+        if (a.y > p.y) != (b.y > p.y) {
+            let (low, high) = if a.y < b.y { (a, b) } else { (b, a) };
+            if orientation(low, high, p) == Orientation::CounterClockwise {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn v(x: i64, y: i64) -> Vec2<i64> {
+        Vec2::new(x, y)
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = [
+            v(0, 0),
+            v(2, 0),
+            v(4, 0),
+            v(1, 1),
+            v(2, 2),
+            v(4, 4),
+            v(0, 4),
+            v(3, 1),
+            v(0, 2),
+            v(4, 0),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![v(0, 0), v(4, 0), v(4, 4), v(0, 4)]
+        );
+        assert_eq!(area2(&convex_hull(&points)), 32);
+
+        assert_eq!(convex_hull(&[v(1, 1), v(1, 1)]), vec![v(1, 1)]);
+        assert_eq!(
+            convex_hull(&[v(0, 0), v(1, 1), v(2, 2)]),
+            vec![v(0, 0), v(2, 2)]
+        );
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        // An L shape, clockwise
+        let polygon = [v(0, 0), v(0, 4), v(2, 4), v(2, 2), v(4, 2), v(4, 0)];
+        assert_eq!(area2(&polygon), -24);
+        assert_eq!(point_in_polygon(&polygon, v(1, 1)), Containment::Inside);
+        assert_eq!(point_in_polygon(&polygon, v(1, 3)), Containment::Inside);
+        assert_eq!(point_in_polygon(&polygon, v(3, 3)), Containment::Outside);
+        assert_eq!(point_in_polygon(&polygon, v(2, 3)), Containment::Boundary);
+        assert_eq!(point_in_polygon(&polygon, v(4, 0)), Containment::Boundary);
+        assert_eq!(point_in_polygon(&polygon, v(-1, 2)), Containment::Outside);
+        assert_eq!(point_in_polygon(&polygon, v(1, 2)), Containment::Inside);
+    }
+}
@@ -0,0 +1,377 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::{
+    aoc::grid::{Direction, Point},
+    quant::NumConsts,
+};
+
+/// What the vector maths needs from a component type. Implemented for every
+/// primitive integer and float.
+pub trait Component:
+    Copy
+    + PartialOrd
+    + NumConsts
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+}
+
+impl<T> Component for T where
+    T: Copy
+        + PartialOrd
+        + NumConsts
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+{
+}
+
+fn abs<T: Component>(x: T) -> T {
+    if x < T::ZERO { T::ZERO - x } else { x }
+}
+
+fn max<T: Component>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Component> Vec2<T> {
+    pub const ZERO: Self = Self::new(T::ZERO, T::ZERO);
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The z component of the 3D cross product, positive when `other` is
+    /// anticlockwise from `self` (with y pointing up).
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    pub fn manhattan(&self) -> T {
+        abs(self.x) + abs(self.y)
+    }
+
+    pub fn chebyshev(&self) -> T {
+        max(abs(self.x), abs(self.y))
+    }
+
+    pub fn norm_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*self - *other).manhattan()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (*self - *other).chebyshev()
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*self - *other).norm_squared()
+    }
+
+    pub fn map<U, F: Fn(T) -> U>(&self, f: F) -> Vec2<U> {
+        Vec2::new(f(self.x), f(self.y))
+    }
+
+    pub fn array(&self) -> [T; 2] {
+        [self.x, self.y]
+    }
+}
+
+impl<T: Component> Vec3<T> {
+    pub const ZERO: Self = Self::new(T::ZERO, T::ZERO, T::ZERO);
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn manhattan(&self) -> T {
+        abs(self.x) + abs(self.y) + abs(self.z)
+    }
+
+    pub fn chebyshev(&self) -> T {
+        max(max(abs(self.x), abs(self.y)), abs(self.z))
+    }
+
+    pub fn norm_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*self - *other).manhattan()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (*self - *other).chebyshev()
+    }
+
+    pub fn distance_squared(&self, other: &Self) -> T {
+        (*self - *other).norm_squared()
+    }
+
+    pub fn map<U, F: Fn(T) -> U>(&self, f: F) -> Vec3<U> {
+        Vec3::new(f(self.x), f(self.y), f(self.z))
+    }
+
+    pub fn xy(&self) -> Vec2<T> {
+        Vec2::new(self.x, self.y)
+    }
+
+    pub fn array(&self) -> [T; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+macro_rules! impl_vec_ops {
+    ($vec:ident, $($field:ident),+) => {
+        impl<T: Component> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field + rhs.$field),+ }
+            }
+        }
+
+        impl<T: Component> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self { $($field: self.$field - rhs.$field),+ }
+            }
+        }
+
+        impl<T: Component> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field * rhs),+ }
+            }
+        }
+
+        impl<T: Component> Div<T> for $vec<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self::Output {
+                Self { $($field: self.$field / rhs),+ }
+            }
+        }
+
+        impl<T: Component + Neg<Output = T>> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Component> AddAssign for $vec<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Component> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Component> std::iter::Sum for $vec<T> {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, |acc, v| acc + v)
+            }
+        }
+
+        /// Parses comma separated components, e.g. `19, 13, 30`.
+        impl<T: FromStr> FromStr for $vec<T> {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut parts = s.split(',').map(str::trim);
+                let v = Self {
+                    $($field: parts
+                        .next()
+                        .and_then(|part| part.parse().ok())
+                        .ok_or_else(|| format!("Invalid vector {s}"))?),+
+                };
+                if parts.next().is_some() {
+                    Err(format!("Too many components in {s}"))?
+                }
+                Ok(v)
+            }
+        }
+    };
+}
+impl_vec_ops!(Vec2, x, y);
+impl_vec_ops!(Vec3, x, y, z);
+
+impl<T: Display> Display for Vec2<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl<T: Display> Display for Vec3<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+impl<T> From<(T, T)> for Vec2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T> From<(T, T, T)> for Vec3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+macro_rules! impl_from_point {
+    ($typ:ty) => {
+        impl From<Point> for Vec2<$typ> {
+            fn from(p: Point) -> Self {
+                Self::new(p.x as $typ, p.y as $typ)
+            }
+        }
+    };
+}
+impl_from_point!(u32);
+impl_from_point!(usize);
+impl_from_point!(u64);
+impl_from_point!(u128);
+impl_from_point!(i32);
+impl_from_point!(isize);
+impl_from_point!(i64);
+impl_from_point!(i128);
+impl_from_point!(f64);
+
+// Directions have negative components, so only the signed types above
+macro_rules! impl_from_direction {
+    ($typ:ty) => {
+        impl From<Direction> for Vec2<$typ> {
+            fn from(d: Direction) -> Self {
+                match d {
+                    Direction::Up => Self::new(0 as $typ, -1 as $typ),
+                    Direction::Down => Self::new(0 as $typ, 1 as $typ),
+                    Direction::Left => Self::new(-1 as $typ, 0 as $typ),
+                    Direction::Right => Self::new(1 as $typ, 0 as $typ),
+                }
+            }
+        }
+    };
+}
+impl_from_direction!(i32);
+impl_from_direction!(isize);
+impl_from_direction!(i64);
+impl_from_direction!(i128);
+impl_from_direction!(f64);
+
+impl<T: Component + TryInto<usize>> Vec2<T> {
+    /// The grid point at this position, if both components are non-negative
+    /// and fit in a `usize`.
+    pub fn to_point(&self) -> Option<Point> {
+        Some(Point::new(self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vec2() {
+        let a = Vec2::new(3i64, -4);
+        let b = Vec2::new(1i64, 2);
+        assert_eq!(a + b, Vec2::new(4, -2));
+        assert_eq!(a - b, Vec2::new(2, -6));
+        assert_eq!(a * 2, Vec2::new(6, -8));
+        assert_eq!(-a, Vec2::new(-3, 4));
+        assert_eq!(a.dot(&b), -5);
+        assert_eq!(a.cross(&b), 10);
+        assert_eq!(a.manhattan(), 7);
+        assert_eq!(a.chebyshev(), 4);
+        assert_eq!(a.norm_squared(), 25);
+        assert_eq!(a.manhattan_distance(&b), 8);
+
+        let p = Point::new(2, 5);
+        assert_eq!(Vec2::<i32>::from(p), Vec2::new(2, 5));
+        assert_eq!(Vec2::<u32>::from(p), Vec2::new(2, 5));
+        assert_eq!(Vec2::<i32>::from(Direction::Up), Vec2::new(0, -1));
+    }
+
+    #[test]
+    fn test_vec3() {
+        let x = Vec3::new(1i64, 0, 0);
+        let y = Vec3::new(0i64, 1, 0);
+        assert_eq!(x.cross(&y), Vec3::new(0, 0, 1));
+        assert_eq!(y.cross(&x), Vec3::new(0, 0, -1));
+
+        let a: Vec3<i64> = "19, 13, 30".parse().unwrap();
+        let v: Vec3<i64> = " -2,  1, -2".parse().unwrap();
+        assert_eq!(a + v * 5, Vec3::new(9, 18, 20));
+        assert_eq!(a.chebyshev_distance(&Vec3::ZERO), 30);
+        assert!("1, 2".parse::<Vec3<i64>>().is_err());
+        assert!("1, 2, 3, 4".parse::<Vec3<i64>>().is_err());
+
+        let f = Vec3::new(1.5f64, -2.0, 0.5);
+        assert_eq!(f.manhattan(), 4.0);
+        assert_eq!(f.norm_squared(), 6.5);
+    }
+
+    #[test]
+    fn test_points() {
+        let p = Point::new(3, 7);
+        let v: Vec2<i64> = p.into();
+        assert_eq!(v, Vec2::new(3, 7));
+        assert_eq!(
+            (v + Direction::Up.into()).to_point(),
+            Some(Point::new(3, 6))
+        );
+        assert_eq!(Vec2::new(-1i64, 0).to_point(), None);
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::HashMap, hash::Hash, str::FromStr};
+
+mod clique;
+mod components;
+mod flow;
+mod longest;
+mod matching;
+mod order;
+
+pub use components::Condensation;
+pub use flow::{Dinic, MinCut};
+pub use matching::{Matching, assignment, hopcroft_karp};
+
+/// Directed graph with node weights `N` and edge weights `E`. Nodes are
+/// interned, so adding the same `N` twice gives back the same index, and
+/// every algorithm works in terms of those dense indices.
+///
+/// Undirected graphs store each edge both ways, see `add_undirected_edge`.
+#[derive(Debug, Clone)]
+pub struct Graph<N, E = ()> {
+    nodes: Vec<N>,
+    adjacency: Vec<Vec<(usize, E)>>,
+    index: HashMap<N, usize>,
+}
+
+impl<N, E> Default for Graph<N, E> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            adjacency: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The index of `node`, adding it if new.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        let i = self.nodes.len();
+        self.index.insert(node.clone(), i);
+        self.nodes.push(node);
+        self.adjacency.push(Vec::new());
+        i
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    /// Add an edge between two nodes by value, adding them if new.
+    pub fn connect(&mut self, a: N, b: N, weight: E) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.add_edge(a, b, weight);
+    }
+
+    pub fn from_edges<I: IntoIterator<Item = (N, N, E)>>(edges: I) -> Self {
+        let mut graph = Self::new();
+        for (a, b, weight) in edges {
+            graph.connect(a, b, weight);
+        }
+        graph
+    }
+}
+
+impl<E> Graph<usize, E> {
+    /// Nodes `0..n`, each its own index.
+    pub fn with_nodes(n: usize) -> Self {
+        Self {
+            nodes: (0..n).collect(),
+            adjacency: (0..n).map(|_| Vec::new()).collect(),
+            index: (0..n).map(|i| (i, i)).collect(),
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, i: usize) -> &N {
+        &self.nodes[i]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn add_edge(&mut self, a: usize, b: usize, weight: E) {
+        self.adjacency[a].push((b, weight));
+    }
+
+    /// Outgoing edges of `i` as `(target, weight)`.
+    pub fn neighbours(&self, i: usize) -> &[(usize, E)] {
+        &self.adjacency[i]
+    }
+
+    /// Every edge as `(from, to, weight)`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, &E)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(a, out)| out.iter().map(move |(b, w)| (a, *b, w)))
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum()
+    }
+}
+
+impl<N, E: Clone> Graph<N, E> {
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: E) {
+        self.add_edge(a, b, weight.clone());
+        self.add_edge(b, a, weight);
+    }
+}
+
+/// Parses lines of `a -> b, c`, also accepting `a: b c`, into edges from
+/// `a` to each target. Nodes are named by their strings.
+impl FromStr for Graph<String> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut graph = Self::new();
+        for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (from, targets) = line
+                .split_once("->")
+                .or_else(|| line.split_once(':'))
+                .ok_or_else(|| format!("Expected a -> b, c, got {line}"))?;
+            let from = from.trim();
+            if from.is_empty() {
+                Err(format!("Missing source node in {line}"))?;
+            }
+            let from = graph.add_node(from.to_owned());
+            for target in targets
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|t| !t.is_empty())
+            {
+                let target = graph.add_node(target.to_owned());
+                graph.add_edge(from, target, ());
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let graph: Graph<String> = "you -> bbb, ccc\nbbb -> ddd\nccc: ddd eee\nddd ->"
+            .parse()
+            .unwrap();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.edge_count(), 5);
+        let ccc = graph.index_of(&"ccc".to_owned()).unwrap();
+        let targets: Vec<&str> = graph
+            .neighbours(ccc)
+            .iter()
+            .map(|&(t, _)| graph.node(t).as_str())
+            .collect();
+        assert_eq!(targets, vec!["ddd", "eee"]);
+        assert!("no arrow".parse::<Graph<String>>().is_err());
+    }
+
+    #[test]
+    fn test_interning() {
+        let graph = Graph::from_edges([("a", "b", 3), ("b", "c", 4), ("a", "c", 5)]);
+        assert_eq!(graph.nodes(), &["a", "b", "c"]);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![(0, 1, &3), (0, 2, &5), (1, 2, &4)]
+        );
+    }
+}
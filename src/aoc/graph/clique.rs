@@ -0,0 +1,177 @@
+use super::Graph;
+
+/// Fixed size set of node indices packed into words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BitSet(Vec<u64>);
+
+impl BitSet {
+    fn new(n: usize) -> Self {
+        Self(vec![0; n.div_ceil(64)])
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    fn remove(&mut self, i: usize) {
+        self.0[i / 64] &= !(1 << (i % 64));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.iter().all(|&w| w == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        Self(self.0.iter().zip(&other.0).map(|(a, b)| a & b).collect())
+    }
+
+    fn and_not(&self, other: &Self) -> Self {
+        Self(self.0.iter().zip(&other.0).map(|(a, b)| a & !b).collect())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+struct Search {
+    adjacency: Vec<BitSet>,
+    maximum_only: bool,
+    cliques: Vec<Vec<usize>>,
+    best: Vec<usize>,
+}
+
+impl Search {
+    // Bron–Kerbosch with the pivot chosen to leave the fewest candidates
+    fn expand(&mut self, clique: &mut Vec<usize>, mut candidates: BitSet, mut excluded: BitSet) {
+        if candidates.is_empty() {
+            if excluded.is_empty() {
+                let mut found = clique.clone();
+                found.sort_unstable();
+                if self.maximum_only {
+                    if (found.len(), std::cmp::Reverse(&found))
+                        > (self.best.len(), std::cmp::Reverse(&self.best))
+                    {
+                        self.best = found;
+                    }
+                } else {
+                    self.cliques.push(found);
+                }
+            }
+            return;
+        }
+        // Strictly smaller so equal sized cliques still get the tie break
+        if self.maximum_only && clique.len() + candidates.len() < self.best.len() {
+            return;
+        }
+
+        let pivot = candidates
+            .iter()
+            .chain(excluded.iter())
+            .max_by_key(|&u| candidates.and(&self.adjacency[u]).len())
+            .unwrap();
+        for v in candidates
+            .and_not(&self.adjacency[pivot])
+            .iter()
+            .collect::<Vec<_>>()
+        {
+            clique.push(v);
+            let neighbours = &self.adjacency[v];
+            let (next_candidates, next_excluded) =
+                (candidates.and(neighbours), excluded.and(neighbours));
+            self.expand(clique, next_candidates, next_excluded);
+            clique.pop();
+            candidates.remove(v);
+            excluded.insert(v);
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    // Edges count in either direction and self loops are ignored
+    fn search(&self, maximum_only: bool) -> Search {
+        let n = self.len();
+        let mut adjacency = vec![BitSet::new(n); n];
+        for (a, b, _) in self.edges() {
+            if a != b {
+                adjacency[a].insert(b);
+                adjacency[b].insert(a);
+            }
+        }
+        let mut search = Search {
+            adjacency,
+            maximum_only,
+            cliques: Vec::new(),
+            best: Vec::new(),
+        };
+        let mut all = BitSet::new(n);
+        (0..n).for_each(|v| all.insert(v));
+        search.expand(&mut Vec::new(), all, BitSet::new(n));
+        search
+    }
+
+    /// Every maximal clique, each sorted and the list sorted, treating edges
+    /// as undirected.
+    pub fn maximal_cliques(&self) -> Vec<Vec<usize>> {
+        let mut cliques = self.search(false).cliques;
+        cliques.sort_unstable();
+        cliques
+    }
+
+    /// A largest clique, sorted, preferring the lexicographically smallest
+    /// on ties.
+    pub fn maximum_clique(&self) -> Vec<usize> {
+        self.search(true).best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cliques() {
+        let input = "\
+            kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc
+            yn-cg kh-ub ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg
+            wq-ub ub-vc de-ta wq-aq wq-vc wh-yn ka-de kh-ta co-tc wh-qp
+            tb-vc td-yn";
+        let mut graph: Graph<&str> = Graph::new();
+        for pair in input.split_whitespace() {
+            let (a, b) = pair.split_once('-').unwrap();
+            graph.connect(a, b, ());
+        }
+
+        let best = graph.maximum_clique();
+        let mut names: Vec<&str> = best.iter().map(|&v| *graph.node(v)).collect();
+        names.sort_unstable();
+        assert_eq!(names.join(","), "co,de,ka,ta");
+
+        let cliques = graph.maximal_cliques();
+        assert!(cliques.contains(&best));
+        for clique in &cliques {
+            for (i, &a) in clique.iter().enumerate() {
+                for &b in &clique[i + 1..] {
+                    assert!(
+                        graph.neighbours(a).iter().any(|&(t, _)| t == b)
+                            || graph.neighbours(b).iter().any(|&(t, _)| t == a)
+                    );
+                }
+            }
+        }
+        assert_eq!(cliques.iter().filter(|c| c.len() == 4).count(), 1);
+    }
+}
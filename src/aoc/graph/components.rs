@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+
+use super::Graph;
+
+/// Each strongly connected component collapsed to one node, giving a DAG.
+#[derive(Debug, Clone)]
+pub struct Condensation {
+    /// Nodes are the members of each component, numbered in topological
+    /// order.
+    pub dag: Graph<Vec<usize>>,
+    /// The component of each node in the original graph.
+    pub component: Vec<usize>,
+}
+
+impl<N, E> Graph<N, E> {
+    /// Tarjan's algorithm, without recursion. Components come out in reverse
+    /// topological order: no edge leads from a component to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        const UNSEEN: usize = usize::MAX;
+        let n = self.len();
+        let mut index = vec![UNSEEN; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut counter = 0;
+
+        for start in 0..n {
+            if index[start] != UNSEEN {
+                continue;
+            }
+            // (node, next edge to look at)
+            let mut calls = vec![(start, 0)];
+            index[start] = counter;
+            low[start] = counter;
+            counter += 1;
+            stack.push(start);
+            on_stack[start] = true;
+
+            while let Some(&mut (v, ref mut next)) = calls.last_mut() {
+                if let Some(&(w, _)) = self.neighbours(v).get(*next) {
+                    *next += 1;
+                    if index[w] == UNSEEN {
+                        index[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        stack.push(w);
+                        on_stack[w] = true;
+                        calls.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if low[v] == index[v] {
+                    let mut component = Vec::new();
+                    loop {
+                        let w = stack.pop().unwrap();
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[v]);
+                }
+            }
+        }
+        components
+    }
+
+    pub fn condensation(&self) -> Condensation {
+        let mut components = self.strongly_connected_components();
+        components.reverse();
+        let mut component = vec![0; self.len()];
+        for (c, members) in components.iter().enumerate() {
+            for &v in members {
+                component[v] = c;
+            }
+        }
+
+        let mut dag = Graph::new();
+        for members in components {
+            dag.add_node(members);
+        }
+        let mut seen = HashSet::new();
+        for (a, b, _) in self.edges() {
+            let (ca, cb) = (component[a], component[b]);
+            if ca != cb && seen.insert((ca, cb)) {
+                dag.add_edge(ca, cb, ());
+            }
+        }
+        Condensation { dag, component }
+    }
+
+    // Depth first search for low links over the graph as undirected, with
+    // each edge stored both ways. Returns (articulation points, bridges).
+    fn low_links(&self) -> (Vec<usize>, Vec<(usize, usize)>) {
+        const UNSEEN: usize = usize::MAX;
+        let n = self.len();
+        let mut discovered = vec![UNSEEN; n];
+        let mut low = vec![0; n];
+        let mut is_cut = vec![false; n];
+        let mut bridges = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..n {
+            if discovered[root] != UNSEEN {
+                continue;
+            }
+            discovered[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            let mut root_children = 0;
+            // (node, parent, next edge, whether the edge back to the parent
+            // has been skipped yet); only one is skipped so parallel edges
+            // still count
+            let mut calls = vec![(root, UNSEEN, 0, false)];
+
+            while let Some(&mut (v, parent, ref mut next, ref mut skipped)) = calls.last_mut() {
+                if let Some(&(w, _)) = self.neighbours(v).get(*next) {
+                    *next += 1;
+                    if w == parent && !*skipped {
+                        *skipped = true;
+                    } else if discovered[w] == UNSEEN {
+                        discovered[w] = counter;
+                        low[w] = counter;
+                        counter += 1;
+                        if v == root {
+                            root_children += 1;
+                        }
+                        calls.push((w, v, 0, false));
+                    } else {
+                        low[v] = low[v].min(discovered[w]);
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if parent != UNSEEN {
+                    low[parent] = low[parent].min(low[v]);
+                    if low[v] > discovered[parent] {
+                        bridges.push((parent.min(v), parent.max(v)));
+                    }
+                    if parent != root && low[v] >= discovered[parent] {
+                        is_cut[parent] = true;
+                    }
+                }
+            }
+            if root_children > 1 {
+                is_cut[root] = true;
+            }
+        }
+
+        bridges.sort_unstable();
+        let cuts = (0..n).filter(|&v| is_cut[v]).collect();
+        (cuts, bridges)
+    }
+
+    /// Nodes whose removal disconnects their component, treating the graph as
+    /// undirected with each edge stored both ways.
+    pub fn articulation_points(&self) -> Vec<usize> {
+        self.low_links().0
+    }
+
+    /// Edges whose removal disconnects their component, as `(a, b)` with
+    /// `a < b`, treating the graph as undirected with each edge stored both
+    /// ways.
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        self.low_links().1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn directed(n: usize, edges: &[(usize, usize)]) -> Graph<usize> {
+        let mut graph = Graph::with_nodes(n);
+        for &(a, b) in edges {
+            graph.add_edge(a, b, ());
+        }
+        graph
+    }
+
+    fn undirected(n: usize, edges: &[(usize, usize)]) -> Graph<usize> {
+        let mut graph = Graph::with_nodes(n);
+        for &(a, b) in edges {
+            graph.add_undirected_edge(a, b, ());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_scc() {
+        let graph = directed(
+            8,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 3),
+                (6, 5),
+                (6, 7),
+                (7, 6),
+            ],
+        );
+        let mut components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 3);
+        components.sort();
+        assert_eq!(components, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7]]);
+
+        let condensation = graph.condensation();
+        assert_eq!(condensation.dag.len(), 3);
+        assert_eq!(condensation.dag.edge_count(), 2);
+        assert!(condensation.dag.topological_sort().unwrap().is_sorted());
+        assert_eq!(condensation.component[0], condensation.component[2]);
+        let sink = condensation.component[4];
+        assert!(condensation.dag.neighbours(sink).is_empty());
+    }
+
+    #[test]
+    fn test_articulation_points_and_bridges() {
+        // Two triangles joined by the bridge 2-3, with a tail 5-6
+        let graph = undirected(
+            7,
+            &[
+                (0, 1),
+                (1, 2),
+                (2, 0),
+                (2, 3),
+                (3, 4),
+                (4, 5),
+                (5, 3),
+                (5, 6),
+            ],
+        );
+        assert_eq!(graph.articulation_points(), vec![2, 3, 5]);
+        assert_eq!(graph.bridges(), vec![(2, 3), (5, 6)]);
+
+        // A doubled edge is not a bridge
+        let doubled = undirected(3, &[(0, 1), (0, 1), (1, 2)]);
+        assert_eq!(doubled.bridges(), vec![(1, 2)]);
+        assert_eq!(doubled.articulation_points(), vec![1]);
+    }
+}
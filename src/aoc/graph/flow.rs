@@ -0,0 +1,374 @@
+use std::{
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+use super::Graph;
+
+/// Dinic's maximum flow over `0..n` with integer capacities.
+#[derive(Debug, Clone)]
+pub struct Dinic {
+    adjacency: Vec<Vec<usize>>,
+    // Edge `2k` is the `k`th edge added and `2k + 1` its reverse
+    to: Vec<usize>,
+    residual: Vec<u64>,
+    capacity: Vec<u64>,
+    level: Vec<usize>,
+    next: Vec<usize>,
+}
+
+impl Dinic {
+    pub fn new(n: usize) -> Self {
+        Self {
+            adjacency: vec![Vec::new(); n],
+            to: Vec::new(),
+            residual: Vec::new(),
+            capacity: Vec::new(),
+            level: vec![0; n],
+            next: vec![0; n],
+        }
+    }
+
+    /// Add an edge from `a` to `b`, returning its id for `flow`.
+    pub fn add_edge(&mut self, a: usize, b: usize, capacity: u64) -> usize {
+        let id = self.to.len() / 2;
+        for (from, to, cap) in [(a, b, capacity), (b, a, 0)] {
+            self.adjacency[from].push(self.to.len());
+            self.to.push(to);
+            self.residual.push(cap);
+            self.capacity.push(cap);
+        }
+        id
+    }
+
+    /// Flow currently along edge `id`.
+    pub fn flow(&self, id: usize) -> u64 {
+        self.capacity[2 * id] - self.residual[2 * id]
+    }
+
+    /// Push as much further flow as possible from `source` to `sink`,
+    /// returning the amount. Panics if `source == sink`, where the flow would
+    /// be unbounded.
+    pub fn max_flow(&mut self, source: usize, sink: usize) -> u64 {
+        assert_ne!(source, sink, "max flow needs distinct source and sink");
+        let mut total = 0;
+        while self.build_levels(source, sink) {
+            self.next.fill(0);
+            loop {
+                let pushed = self.augment(source, sink);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+        total
+    }
+
+    fn build_levels(&mut self, source: usize, sink: usize) -> bool {
+        self.level.fill(usize::MAX);
+        self.level[source] = 0;
+        let mut queue = VecDeque::from([source]);
+        while let Some(v) = queue.pop_front() {
+            for &e in &self.adjacency[v] {
+                let w = self.to[e];
+                if self.residual[e] > 0 && self.level[w] == usize::MAX {
+                    self.level[w] = self.level[v] + 1;
+                    queue.push_back(w);
+                }
+            }
+        }
+        self.level[sink] != usize::MAX
+    }
+
+    // One blocking-flow path found by depth first search along the levels,
+    // kept as an explicit stack of edges so long paths can't overflow the
+    // call stack
+    fn augment(&mut self, source: usize, sink: usize) -> u64 {
+        let mut path: Vec<usize> = Vec::new();
+        let mut v = source;
+        loop {
+            if v == sink {
+                // Never empty, as max_flow rules out source == sink
+                let pushed = path.iter().map(|&e| self.residual[e]).min().unwrap();
+                for &e in &path {
+                    self.residual[e] -= pushed;
+                    self.residual[e ^ 1] += pushed;
+                }
+                return pushed;
+            }
+
+            let mut advanced = false;
+            while self.next[v] < self.adjacency[v].len() {
+                let e = self.adjacency[v][self.next[v]];
+                let w = self.to[e];
+                if self.residual[e] > 0 && self.level[w] == self.level[v] + 1 {
+                    path.push(e);
+                    v = w;
+                    advanced = true;
+                    break;
+                }
+                self.next[v] += 1;
+            }
+
+            if !advanced {
+                // Dead end: back up and skip the edge that led here
+                let Some(e) = path.pop() else {
+                    return 0;
+                };
+                v = self.to[e ^ 1];
+                self.next[v] += 1;
+            }
+        }
+    }
+
+    /// After `max_flow`, which nodes the source can still reach.
+    pub fn source_side(&self, source: usize) -> Vec<bool> {
+        let mut seen = vec![false; self.adjacency.len()];
+        seen[source] = true;
+        let mut stack = vec![source];
+        while let Some(v) = stack.pop() {
+            for &e in &self.adjacency[v] {
+                let w = self.to[e];
+                if self.residual[e] > 0 && !seen[w] {
+                    seen[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// A minimum cut splitting the graph in two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    pub value: u64,
+    /// Edges crossing the cut, as `(from, to)` for `MinCut`s between two
+    /// nodes and `(a, b)` with `a < b` for global ones.
+    pub edges: Vec<(usize, usize)>,
+    pub source_side: Vec<usize>,
+    pub sink_side: Vec<usize>,
+}
+
+impl<N, E> Graph<N, E> {
+    /// The minimum `source`-`sink` cut by max-flow, with `capacity` giving
+    /// each edge's capacity. Panics if `source == sink`, which no cut can
+    /// separate.
+    pub fn min_cut<F>(&self, source: usize, sink: usize, capacity: F) -> MinCut
+    where
+        F: Fn(&E) -> u64,
+    {
+        assert_ne!(source, sink, "min cut needs distinct source and sink");
+        let mut dinic = Dinic::new(self.len());
+        for (a, b, weight) in self.edges() {
+            dinic.add_edge(a, b, capacity(weight));
+        }
+        let value = dinic.max_flow(source, sink);
+        let reachable = dinic.source_side(source);
+
+        let edges = self
+            .edges()
+            .filter(|&(a, b, w)| reachable[a] && !reachable[b] && capacity(w) > 0)
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        let (source_side, sink_side) = (0..self.len()).partition(|&v| reachable[v]);
+        MinCut {
+            value,
+            edges,
+            source_side,
+            sink_side,
+        }
+    }
+
+    /// Stoer–Wagner global minimum cut, treating the graph as undirected
+    /// with each edge stored both ways. `None` for fewer than two nodes.
+    pub fn global_min_cut<F>(&self, capacity: F) -> Option<MinCut>
+    where
+        F: Fn(&E) -> u64,
+    {
+        let n = self.len();
+        if n < 2 {
+            return None;
+        }
+        let mut weights: Vec<HashMap<usize, u64>> = vec![HashMap::new(); n];
+        for (a, b, w) in self.edges() {
+            if a != b {
+                *weights[a].entry(b).or_default() += capacity(w);
+            }
+        }
+        let mut members: Vec<Vec<usize>> = (0..n).map(|v| vec![v]).collect();
+        let mut alive: Vec<usize> = (0..n).collect();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while alive.len() > 1 {
+            // Maximum adjacency ordering, with stale heap entries skipped
+            let mut attached = vec![0u64; n];
+            let mut added = vec![false; n];
+            let mut heap: BinaryHeap<(u64, usize)> = alive.iter().map(|&v| (0, v)).collect();
+            let (mut s, mut t) = (usize::MAX, usize::MAX);
+            while let Some((w, v)) = heap.pop() {
+                if added[v] || w != attached[v] {
+                    continue;
+                }
+                added[v] = true;
+                (s, t) = (t, v);
+                for (&u, &w) in &weights[v] {
+                    if !added[u] {
+                        attached[u] += w;
+                        heap.push((attached[u], u));
+                    }
+                }
+            }
+
+            if best.as_ref().is_none_or(|(b, _)| attached[t] < *b) {
+                best = Some((attached[t], members[t].clone()));
+            }
+
+            // Merge t into s
+            let moved = std::mem::take(&mut members[t]);
+            members[s].extend(moved);
+            for (u, w) in std::mem::take(&mut weights[t]) {
+                weights[u].remove(&t);
+                if u != s {
+                    *weights[s].entry(u).or_default() += w;
+                    *weights[u].entry(s).or_default() += w;
+                }
+            }
+            alive.retain(|&v| v != t);
+        }
+
+        // The best phase's last node plays the sink, so its merged group is
+        // the sink side
+        let (value, sink_group) = best?;
+        let mut on_sink_side = vec![false; n];
+        for &v in &sink_group {
+            on_sink_side[v] = true;
+        }
+        let mut edges: Vec<(usize, usize)> = self
+            .edges()
+            .filter(|&(a, b, w)| on_sink_side[a] != on_sink_side[b] && capacity(w) > 0)
+            .map(|(a, b, _)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        let (source_side, sink_side) = (0..n).partition(|&v| !on_sink_side[v]);
+        Some(MinCut {
+            value,
+            edges,
+            source_side,
+            sink_side,
+        })
+    }
+}
+
+impl<N: Hash + Eq + Clone, E> Graph<N, E> {
+    /// `min_cut` between nodes given by value, `None` if either is missing.
+    pub fn min_cut_between<F>(&self, source: &N, sink: &N, capacity: F) -> Option<MinCut>
+    where
+        F: Fn(&E) -> u64,
+    {
+        Some(self.min_cut(self.index_of(source)?, self.index_of(sink)?, capacity))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        // The CLRS example network, max flow 23
+        let mut graph = Graph::with_nodes(6);
+        for (a, b, c) in [
+            (0, 1, 16),
+            (0, 2, 13),
+            (2, 1, 4),
+            (1, 3, 12),
+            (3, 2, 9),
+            (2, 4, 14),
+            (4, 3, 7),
+            (3, 5, 20),
+            (4, 5, 4),
+        ] {
+            graph.add_edge(a, b, c);
+        }
+        let cut = graph.min_cut(0, 5, |&c| c);
+        assert_eq!(cut.value, 23);
+        assert_eq!(cut.edges, vec![(1, 3), (4, 3), (4, 5)]);
+        assert_eq!(cut.source_side, vec![0, 1, 2, 4]);
+        assert_eq!(cut.sink_side, vec![3, 5]);
+    }
+
+    #[test]
+    fn test_long_path() {
+        // Deep enough to overflow the stack with a recursive search
+        let n = 200_000;
+        let mut dinic = Dinic::new(n);
+        for v in 0..n - 1 {
+            dinic.add_edge(v, v + 1, 5 + (v % 3) as u64);
+        }
+        assert_eq!(dinic.max_flow(0, n - 1), 5);
+        assert_eq!(dinic.flow(0), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct source and sink")]
+    fn test_flow_to_self() {
+        let mut dinic = Dinic::new(2);
+        dinic.add_edge(0, 1, 3);
+        dinic.max_flow(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct source and sink")]
+    fn test_cut_to_self() {
+        let mut graph = Graph::with_nodes(2);
+        graph.add_edge(0, 1, 3u64);
+        graph.min_cut(1, 1, |&c| c);
+    }
+
+    fn wires() -> Graph<String> {
+        // Two five-cliques joined by three wires
+        let input = "\
+            a: b c d e
+            b: c d e
+            c: d e
+            d: e
+            v: w x y z
+            w: x y z
+            x: y z
+            y: z
+            a: w
+            b: x
+            c: y";
+        let directed: Graph<String> = input.parse().unwrap();
+        let mut graph = Graph::new();
+        for (a, b, _) in directed.edges() {
+            graph.connect(directed.node(a).clone(), directed.node(b).clone(), ());
+            graph.connect(directed.node(b).clone(), directed.node(a).clone(), ());
+        }
+        graph
+    }
+
+    #[test]
+    fn test_cut_three_wires() {
+        let graph = wires();
+        let cut = graph
+            .min_cut_between(&"d".to_owned(), &"z".to_owned(), |_| 1)
+            .unwrap();
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.source_side.len() * cut.sink_side.len(), 25);
+
+        let global = graph.global_min_cut(|_| 1).unwrap();
+        assert_eq!(global.value, 3);
+        assert_eq!(global.edges.len(), 3);
+        assert_eq!(global.source_side.len() * global.sink_side.len(), 25);
+        let names: Vec<(&str, &str)> = global
+            .edges
+            .iter()
+            .map(|&(a, b)| (graph.node(a).as_str(), graph.node(b).as_str()))
+            .collect();
+        assert_eq!(names, vec![("a", "w"), ("b", "x"), ("c", "y")]);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+};
+
+use super::Graph;
+
+// Branches per thread to expand the search tree to before going parallel
+const BRANCHES_PER_THREAD: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+struct State {
+    node: usize,
+    visited: u64,
+    length: u64,
+    // Sum of the heaviest edge out of every unvisited node
+    remaining: u64,
+}
+
+struct Search {
+    // Parallel edges merged, keeping the heaviest
+    adjacency: Vec<Vec<(usize, u64)>>,
+    heaviest: Vec<u64>,
+    to: usize,
+    // Best length plus one, so zero means nothing found yet
+    best: AtomicU64,
+}
+
+impl Search {
+    fn record(&self, length: u64) {
+        self.best.fetch_max(length + 1, Ordering::Relaxed);
+    }
+
+    fn hopeless(&self, state: &State) -> bool {
+        let bound = state.length + state.remaining + self.heaviest[state.node];
+        bound < self.best.load(Ordering::Relaxed)
+    }
+
+    fn children(&self, state: &State) -> impl Iterator<Item = State> + '_ {
+        let state = *state;
+        self.adjacency[state.node]
+            .iter()
+            .filter(move |&&(next, _)| state.visited & (1 << next) == 0)
+            .map(move |&(next, weight)| State {
+                node: next,
+                visited: state.visited | (1 << next),
+                length: state.length + weight,
+                remaining: state.remaining - self.heaviest[next],
+            })
+    }
+
+    fn dfs(&self, state: &State) {
+        if state.node == self.to {
+            self.record(state.length);
+            return;
+        }
+        if self.hopeless(state) {
+            return;
+        }
+        for child in self.children(state) {
+            self.dfs(&child);
+        }
+    }
+}
+
+impl<N, E> Graph<N, E> {
+    /// Length of the longest simple path from `from` to `to`, with `weight`
+    /// giving each edge's length. Exhaustive, so only for small graphs such
+    /// as `Grid::junction_graph`s, with at most 64 nodes. Branches are
+    /// searched in parallel and pruned once even taking the heaviest edge
+    /// out of every unvisited node can't beat the best so far.
+    pub fn longest_path<F>(&self, from: usize, to: usize, weight: F) -> Option<u64>
+    where
+        F: Fn(&E) -> u64,
+    {
+        let n = self.len();
+        assert!(n <= 64, "Longest path search handles at most 64 nodes");
+
+        let mut adjacency: Vec<Vec<(usize, u64)>> = vec![Vec::new(); n];
+        for (a, b, w) in self.edges() {
+            let w = weight(w);
+            match adjacency[a].iter_mut().find(|(t, _)| *t == b) {
+                Some((_, existing)) => *existing = (*existing).max(w),
+                None => adjacency[a].push((b, w)),
+            }
+        }
+        // Nothing leaves the target on a path ending there
+        let heaviest: Vec<u64> = (0..n)
+            .map(|v| {
+                let out = adjacency[v].iter().map(|&(_, w)| w).max();
+                if v == to { 0 } else { out.unwrap_or(0) }
+            })
+            .collect();
+
+        let remaining = heaviest.iter().sum::<u64>() - heaviest[from];
+        let search = Search {
+            adjacency,
+            heaviest,
+            to,
+            best: AtomicU64::new(0),
+        };
+
+        // Breadth first until there is enough work to share out
+        let threads = thread::available_parallelism().map_or(1, |t| t.get());
+        let mut frontier = vec![State {
+            node: from,
+            visited: 1 << from,
+            length: 0,
+            remaining,
+        }];
+        while !frontier.is_empty() && frontier.len() < threads * BRANCHES_PER_THREAD {
+            let mut next = Vec::new();
+            for state in &frontier {
+                if state.node == to {
+                    search.record(state.length);
+                } else {
+                    next.extend(search.children(state));
+                }
+            }
+            frontier = next;
+        }
+
+        let claimed = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..threads.min(frontier.len()) {
+                scope.spawn(|| {
+                    while let Some(state) = frontier.get(claimed.fetch_add(1, Ordering::Relaxed)) {
+                        search.dfs(state);
+                    }
+                });
+            }
+        });
+
+        search.best.into_inner().checked_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::{
+        fixtures::{Lcg, MAZE},
+        grid::{Grid, Point},
+    };
+
+    #[test]
+    fn test_junction_maze() {
+        let grid: Grid<Vec<char>> = MAZE.parse().unwrap();
+        let graph = grid.junction_graph(&[], |&c| c != '#', |_| None);
+        let start = graph.index_of(&Point::new(1, 0)).unwrap();
+        let end = graph.index_of(&Point::new(5, 4)).unwrap();
+        assert_eq!(graph.longest_path(start, end, |&w| w as u64), Some(8));
+        assert_eq!(graph.longest_path(end, end, |&w| w as u64), Some(0));
+    }
+
+    fn brute(graph: &Graph<usize, u64>, v: usize, to: usize, visited: u64) -> Option<u64> {
+        if v == to {
+            return Some(0);
+        }
+        graph
+            .neighbours(v)
+            .iter()
+            .filter(|&&(next, _)| visited & (1 << next) == 0)
+            .filter_map(|&(next, w)| Some(w + brute(graph, next, to, visited | (1 << next))?))
+            .max()
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut rng = Lcg::new(99);
+        for _ in 0..5 {
+            let n = 12;
+            let mut graph = Graph::with_nodes(n);
+            for a in 0..n {
+                for b in a + 1..n {
+                    if rng.below(3) == 0 {
+                        graph.add_undirected_edge(a, b, rng.below(20) + 1);
+                    }
+                }
+            }
+            assert_eq!(
+                graph.longest_path(0, n - 1, |&w| w),
+                brute(&graph, 0, n - 1, 1)
+            );
+        }
+
+        // Unreachable
+        let mut split = Graph::with_nodes(3);
+        split.add_edge(0, 1, 5u64);
+        assert_eq!(split.longest_path(0, 2, |&w| w), None);
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+
+use crate::quant::Matrix;
+
+/// A maximum matching between `left` and `right` nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matching {
+    pub size: usize,
+    /// The right node matched to each left node.
+    pub left: Vec<Option<usize>>,
+    /// The left node matched to each right node.
+    pub right: Vec<Option<usize>>,
+}
+
+/// Hopcroft–Karp maximum bipartite matching, with `edges[l]` listing the
+/// right nodes in `0..right` that left node `l` may be matched to.
+pub fn hopcroft_karp(edges: &[Vec<usize>], right: usize) -> Matching {
+    const FREE: usize = usize::MAX;
+    let left = edges.len();
+    let mut match_left = vec![FREE; left];
+    let mut match_right = vec![FREE; right];
+    let mut distance = vec![0usize; left];
+    let mut size = 0;
+
+    loop {
+        // Layer the free left nodes and everything reachable along
+        // alternating paths
+        let mut queue = VecDeque::new();
+        for l in 0..left {
+            if match_left[l] == FREE {
+                distance[l] = 0;
+                queue.push_back(l);
+            } else {
+                distance[l] = usize::MAX;
+            }
+        }
+        let mut found = false;
+        while let Some(l) = queue.pop_front() {
+            for &r in &edges[l] {
+                match match_right[r] {
+                    FREE => found = true,
+                    next if distance[next] == usize::MAX => {
+                        distance[next] = distance[l] + 1;
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !found {
+            break;
+        }
+
+        for l in 0..left {
+            if match_left[l] == FREE
+                && augment(l, edges, &mut match_left, &mut match_right, &mut distance)
+            {
+                size += 1;
+            }
+        }
+    }
+
+    let wrap = |v: Vec<usize>| v.into_iter().map(|m| (m != FREE).then_some(m)).collect();
+    Matching {
+        size,
+        left: wrap(match_left),
+        right: wrap(match_right),
+    }
+}
+
+// Depth first search for an augmenting path along the layers, with an
+// explicit stack so long paths can't overflow the call stack
+fn augment(
+    root: usize,
+    edges: &[Vec<usize>],
+    match_left: &mut [usize],
+    match_right: &mut [usize],
+    distance: &mut [usize],
+) -> bool {
+    // (left node, index of the edge being tried)
+    let mut stack = vec![(root, 0)];
+    while let Some(&mut (l, ref mut i)) = stack.last_mut() {
+        let Some(&r) = edges[l].get(*i) else {
+            // Dead end, so don't come back this phase
+            distance[l] = usize::MAX;
+            stack.pop();
+            if let Some((_, parent_edge)) = stack.last_mut() {
+                *parent_edge += 1;
+            }
+            continue;
+        };
+        let next = match_right[r];
+        if next == usize::MAX {
+            // Every node on the path takes the edge it was trying
+            for &(l, i) in &stack {
+                let r = edges[l][i];
+                match_left[l] = r;
+                match_right[r] = l;
+            }
+            return true;
+        }
+        if distance[next] == distance[l] + 1 {
+            stack.push((next, 0));
+        } else {
+            *i += 1;
+        }
+    }
+    false
+}
+
+/// Hungarian algorithm for the cheapest assignment of every row to a
+/// distinct column, needing no more rows than columns. Returns the total
+/// cost and the column given to each row.
+pub fn assignment(cost: &Matrix<i64>) -> (i64, Vec<usize>) {
+    let (n, m) = (cost.rows(), cost.cols());
+    assert!(n <= m, "Assignment needs no more rows than columns");
+    const INF: i64 = i64::MAX / 4;
+
+    // 1-indexed potentials with row 0 / column 0 as the sentinel
+    let mut u = vec![0i64; n + 1];
+    let mut v = vec![0i64; m + 1];
+    let mut owner = vec![0usize; m + 1];
+    let mut way = vec![0usize; m + 1];
+
+    for row in 1..=n {
+        owner[0] = row;
+        let mut col = 0;
+        let mut min = vec![INF; m + 1];
+        let mut used = vec![false; m + 1];
+        loop {
+            used[col] = true;
+            let r = owner[col];
+            let mut delta = INF;
+            let mut next = 0;
+            for j in 1..=m {
+                if used[j] {
+                    continue;
+                }
+                let reduced = cost[(r - 1, j - 1)] - u[r] - v[j];
+                if reduced < min[j] {
+                    min[j] = reduced;
+                    way[j] = col;
+                }
+                if min[j] < delta {
+                    delta = min[j];
+                    next = j;
+                }
+            }
+            for j in 0..=m {
+                if used[j] {
+                    u[owner[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    min[j] -= delta;
+                }
+            }
+            col = next;
+            if owner[col] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path
+        while col != 0 {
+            let prev = way[col];
+            owner[col] = owner[prev];
+            col = prev;
+        }
+    }
+
+    let mut columns = vec![0; n];
+    for j in 1..=m {
+        if owner[j] != 0 {
+            columns[owner[j] - 1] = j - 1;
+        }
+    }
+    let total = columns
+        .iter()
+        .enumerate()
+        .map(|(row, &col)| cost[(row, col)])
+        .sum();
+    (total, columns)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // Allergen style elimination: each left item can take some rights
+        let edges = vec![vec![0, 1], vec![0], vec![1, 2], vec![2, 3], vec![3]];
+        let matching = hopcroft_karp(&edges, 4);
+        assert_eq!(matching.size, 4);
+        for (l, r) in matching.left.iter().enumerate() {
+            if let Some(r) = r {
+                assert!(edges[l].contains(r));
+                assert_eq!(matching.right[*r], Some(l));
+            }
+        }
+
+        // Left nodes 0 and 2 both only fit right node 1, so no perfect matching
+        let maximum = hopcroft_karp(&[vec![1], vec![0, 2], vec![1]], 3);
+        assert_eq!(maximum.size, 2);
+    }
+
+    #[test]
+    fn test_long_augmenting_path() {
+        // The first phase matches each left node to its own right node, so
+        // the last left node can only get in by shifting everyone along
+        let n = 200_000;
+        let mut edges: Vec<Vec<usize>> = (0..n - 1).map(|i| vec![i, i + 1]).collect();
+        edges.push(vec![0]);
+        let matching = hopcroft_karp(&edges, n);
+        assert_eq!(matching.size, n);
+        assert_eq!(matching.left[n - 1], Some(0));
+        assert_eq!(matching.left[0], Some(1));
+    }
+
+    #[test]
+    fn test_assignment() {
+        let cost = Matrix::from_rows(vec![
+            vec![9, 2, 7, 8],
+            vec![6, 4, 3, 7],
+            vec![5, 8, 1, 8],
+            vec![7, 6, 9, 4],
+        ]);
+        assert_eq!(assignment(&cost), (13, vec![1, 0, 2, 3]));
+
+        // Fewer rows than columns, with negative costs
+        let wide = Matrix::from_rows(vec![vec![-1, -5, 0], vec![-3, -6, 2]]);
+        assert_eq!(assignment(&wide), (-8, vec![1, 0]));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::Graph;
+
+impl<N, E> Graph<N, E> {
+    /// Nodes ordered so every edge goes forwards, taking the smallest
+    /// available index first. On failure returns a cycle `[c0, c1, ..]` with
+    /// edges `c0 -> c1 -> .. -> c0`.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, Vec<usize>> {
+        let mut indegree = vec![0usize; self.len()];
+        for (_, b, _) in self.edges() {
+            indegree[b] += 1;
+        }
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..self.len())
+            .filter(|&v| indegree[v] == 0)
+            .map(Reverse)
+            .collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(Reverse(v)) = ready.pop() {
+            order.push(v);
+            for &(w, _) in self.neighbours(v) {
+                indegree[w] -= 1;
+                if indegree[w] == 0 {
+                    ready.push(Reverse(w));
+                }
+            }
+        }
+        if order.len() == self.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle(&indegree))
+        }
+    }
+
+    // Every node Kahn's algorithm left behind has an incoming edge from
+    // another one left behind, so walking those edges backwards must loop
+    fn find_cycle(&self, indegree: &[usize]) -> Vec<usize> {
+        let mut predecessor = vec![usize::MAX; self.len()];
+        for (a, b, _) in self.edges() {
+            if indegree[a] > 0 && indegree[b] > 0 && predecessor[b] == usize::MAX {
+                predecessor[b] = a;
+            }
+        }
+        let mut seen = vec![false; self.len()];
+        let mut v = indegree.iter().position(|&d| d > 0).unwrap();
+        while !seen[v] {
+            seen[v] = true;
+            v = predecessor[v];
+        }
+        let mut cycle = vec![v];
+        let mut u = predecessor[v];
+        while u != v {
+            cycle.push(u);
+            u = predecessor[u];
+        }
+        cycle.reverse();
+        cycle
+    }
+
+    /// Number of distinct paths from `from` to `to`, `None` if that
+    /// overflows a `u64`. Only the part of the graph reachable from `from`
+    /// has to be acyclic; a cycle there is returned as the error, even if it
+    /// can't reach `to`.
+    pub fn count_paths(&self, from: usize, to: usize) -> Result<Option<u64>, Vec<usize>> {
+        let mut reachable = vec![false; self.len()];
+        reachable[from] = true;
+        let mut stack = vec![from];
+        while let Some(v) = stack.pop() {
+            for &(w, _) in self.neighbours(v) {
+                if !reachable[w] {
+                    reachable[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+
+        // Kahn's algorithm again, restricted to the reachable nodes, which
+        // leaves the indegrees find_cycle expects if it gets stuck
+        let mut indegree = vec![0usize; self.len()];
+        for (a, b, _) in self.edges() {
+            if reachable[a] {
+                indegree[b] += 1;
+            }
+        }
+        let mut ways: Vec<Option<u64>> = vec![Some(0); self.len()];
+        ways[from] = Some(1);
+        let mut ready: Vec<usize> = (indegree[from] == 0).then_some(from).into_iter().collect();
+        let mut visited = 0;
+        while let Some(v) = ready.pop() {
+            visited += 1;
+            for &(w, _) in self.neighbours(v) {
+                ways[w] = ways[w].zip(ways[v]).and_then(|(a, b)| a.checked_add(b));
+                indegree[w] -= 1;
+                if indegree[w] == 0 {
+                    ready.push(w);
+                }
+            }
+        }
+        if visited < reachable.iter().filter(|&&r| r).count() {
+            return Err(self.find_cycle(&indegree));
+        }
+        Ok(ways[to])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_topological_sort() {
+        let graph = Graph::from_edges([
+            (5, 2, ()),
+            (5, 0, ()),
+            (4, 0, ()),
+            (4, 1, ()),
+            (2, 3, ()),
+            (3, 1, ()),
+        ]);
+        let order = graph.topological_sort().unwrap();
+        let position = |n: i32| order.iter().position(|&i| *graph.node(i) == n).unwrap();
+        for (a, b, _) in graph.edges() {
+            assert!(position(*graph.node(a)) < position(*graph.node(b)));
+        }
+
+        let mut cyclic = Graph::with_nodes(5);
+        for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)] {
+            cyclic.add_edge(a, b, ());
+        }
+        let cycle = cyclic.topological_sort().unwrap_err();
+        assert_eq!(cycle.len(), 3);
+        for i in 0..cycle.len() {
+            let (a, b) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            assert!(cyclic.neighbours(a).iter().any(|&(t, _)| t == b));
+        }
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let graph: Graph<String> = "\
+            svr: aaa bbb
+            aaa: fft
+            fft: ccc
+            bbb: tty
+            tty: ccc
+            ccc: ddd eee
+            ddd: hub
+            hub: fff
+            eee: dac
+            dac: fff
+            fff: ggg hhh
+            ggg: out
+            hhh: out"
+            .parse()
+            .unwrap();
+        let node = |name: &str| graph.index_of(&name.to_owned()).unwrap();
+        assert_eq!(graph.count_paths(node("svr"), node("out")), Ok(Some(8)));
+        assert_eq!(graph.count_paths(node("fft"), node("dac")), Ok(Some(1)));
+        assert_eq!(graph.count_paths(node("dac"), node("fft")), Ok(Some(0)));
+
+        // Cycles only matter if they can be reached
+        let mut cyclic = Graph::with_nodes(5);
+        for (a, b) in [(0, 1), (1, 2), (3, 4), (4, 3), (4, 1)] {
+            cyclic.add_edge(a, b, ());
+        }
+        assert_eq!(cyclic.count_paths(0, 2), Ok(Some(1)));
+        let cycle = cyclic.count_paths(3, 2).unwrap_err();
+        assert_eq!(cycle.len(), 2);
+    }
+
+    #[test]
+    fn test_count_paths_overflow() {
+        // Each diamond doubles the number of paths
+        let diamonds = |n: usize| {
+            let mut graph = Graph::with_nodes(3 * n + 1);
+            for i in 0..n {
+                let v = 3 * i;
+                for (a, b) in [(v, v + 1), (v, v + 2), (v + 1, v + 3), (v + 2, v + 3)] {
+                    graph.add_edge(a, b, ());
+                }
+            }
+            graph
+        };
+        assert_eq!(diamonds(63).count_paths(0, 189), Ok(Some(1 << 63)));
+        assert_eq!(diamonds(64).count_paths(0, 192), Ok(None));
+    }
+}
@@ -5,6 +5,8 @@ use std::{
     str::FromStr,
 };
 
+mod junctions;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Point {
     pub x: usize,
@@ -192,7 +194,7 @@ impl<U: Copy> Grid<Vec<U>> {
         Grid::new(
             width,
             height,
-            std::iter::repeat(val).take(width * height).collect(),
+            std::iter::repeat_n(val, width * height).collect(),
         )
     }
 }
@@ -219,6 +221,14 @@ impl<T: Clone + Index<usize>> Clone for Grid<T> {
     }
 }
 
+impl<T: PartialEq + Index<usize>> PartialEq for Grid<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width && self.height == other.height && self.items == other.items
+    }
+}
+
+impl<T: Eq + Index<usize>> Eq for Grid<T> {}
+
 impl<U> FromStr for Grid<Vec<U>>
 where
     U: FromStr,
@@ -382,7 +392,7 @@ impl<T: Index<usize>> GridCell<'_, T> {
             self.down().and_then(|cell| cell.right()),
         ];
 
-        neighbours.into_iter().filter_map(|cell| cell).collect()
+        neighbours.into_iter().flatten().collect()
     }
 }
 
@@ -464,6 +474,17 @@ impl Direction {
             Self::Right => '>',
         }
     }
+
+    /// Inverse of `char`, so slope or arrow characters map to directions.
+    pub const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
 }
 
 impl std::ops::Neg for Direction {
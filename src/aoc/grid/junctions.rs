@@ -0,0 +1,139 @@
+use std::ops::Index;
+
+use super::{Direction, Grid, Point};
+use crate::aoc::graph::Graph;
+
+impl<T: Index<usize>> Grid<T> {
+    /// Collapse corridors into a weighted graph between junctions, the
+    /// passable cells without exactly two passable neighbours (so dead ends
+    /// such as a maze's entrance and exit count too), plus any `keep`
+    /// points. Nodes are added in reading order and each edge is one
+    /// corridor walk, weighted by its length in steps.
+    ///
+    /// `one_way` gives the only direction a cell may be left in, such as for
+    /// slopes. Corridors that would need to break that are left out, and
+    /// parallel corridors between the same junctions each get an edge.
+    pub fn junction_graph<P, O>(
+        &self,
+        keep: &[Point],
+        passable: P,
+        one_way: O,
+    ) -> Graph<Point, usize>
+    where
+        P: Fn(&T::Output) -> bool,
+        O: Fn(&T::Output) -> Option<Direction>,
+    {
+        let open = |p: &Point| self.cell_at_point(p).is_some_and(|c| passable(&c.value()));
+        let exits = |p: Point| {
+            let forced = one_way(&self.cell_at_point(&p).unwrap().value());
+            Direction::all()
+                .iter()
+                .filter(move |&&d| forced.is_none_or(|f| f == d))
+                .filter_map(move |&d| (p + d).filter(open).map(|next| (d, next)))
+        };
+
+        let mut graph = Graph::new();
+        for cell in self.iter() {
+            let p = cell.point();
+            if !open(&p) {
+                continue;
+            }
+            let degree = Direction::all()
+                .iter()
+                .filter(|&&d| (p + d).is_some_and(|n| open(&n)))
+                .count();
+            if degree != 2 || keep.contains(&p) {
+                graph.add_node(p);
+            }
+        }
+
+        for from in 0..graph.len() {
+            let start = *graph.node(from);
+            for (mut heading, mut here) in exits(start) {
+                let mut length = 1;
+                let end = loop {
+                    if let Some(end) = graph.index_of(&here) {
+                        break Some(end);
+                    }
+                    // Never turn back, which leaves the one way on in a
+                    // corridor if any
+                    let Some((d, next)) = exits(here).find(|&(d, _)| d != heading.opposite())
+                    else {
+                        break None;
+                    };
+                    (heading, here) = (d, next);
+                    length += 1;
+                };
+                if let Some(end) = end {
+                    graph.add_edge(from, end, length);
+                }
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::fixtures::MAZE;
+
+    fn maze(input: &str) -> Grid<Vec<char>> {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_junctions() {
+        let grid = maze(MAZE);
+        let graph = grid.junction_graph(&[], |&c| c != '#', |_| None);
+        assert_eq!(
+            graph.nodes(),
+            &[
+                Point::new(1, 0),
+                Point::new(1, 1),
+                Point::new(5, 3),
+                Point::new(5, 4)
+            ]
+        );
+        let mut edges: Vec<_> = graph.edges().map(|(a, b, &w)| (a, b, w)).collect();
+        edges.sort_unstable();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 1, 1),
+                (1, 0, 1),
+                (1, 2, 6),
+                (1, 2, 6),
+                (2, 1, 6),
+                (2, 1, 6),
+                (2, 3, 1),
+                (3, 2, 1)
+            ]
+        );
+
+        let kept = grid.junction_graph(&[Point::new(3, 3)], |&c| c != '#', |_| None);
+        assert_eq!(kept.len(), 5);
+        assert_eq!(kept.edge_count(), 10);
+    }
+
+    #[test]
+    fn test_slopes() {
+        let grid = maze(&MAZE.replacen("#.....#", "#..>..#", 1));
+        let graph = grid.junction_graph(&[], |&c| c != '#', |&c| Direction::from_char(c));
+        // The top corridor can now only be walked left to right
+        let mut edges: Vec<_> = graph.edges().map(|(a, b, &w)| (a, b, w)).collect();
+        edges.sort_unstable();
+        assert_eq!(
+            edges,
+            vec![
+                (0, 1, 1),
+                (1, 0, 1),
+                (1, 2, 6),
+                (1, 2, 6),
+                (2, 1, 6),
+                (2, 3, 1),
+                (3, 2, 1)
+            ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+mod indexed;
+mod radix;
+
+pub use indexed::IndexedHeap;
+pub use radix::RadixHeap;
+
+/// A min-priority queue of `usize` items, so searches can swap heaps.
+///
+/// Heaps without `decrease_key` keep every push, leaving stale entries
+/// behind, so callers should skip popped items whose key is worse than the
+/// best already known. `IndexedHeap` never returns stale entries.
+pub trait MinHeap {
+    type Key: Copy + Ord;
+
+    /// Queue `item` with `key`, or lower its key if already queued.
+    fn push(&mut self, item: usize, key: Self::Key);
+
+    /// Remove an item with the smallest key.
+    fn pop(&mut self) -> Option<(usize, Self::Key)>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Copy + Ord> MinHeap for BinaryHeap<Reverse<(K, usize)>> {
+    type Key = K;
+
+    fn push(&mut self, item: usize, key: K) {
+        BinaryHeap::push(self, Reverse((key, item)));
+    }
+
+    fn pop(&mut self) -> Option<(usize, K)> {
+        BinaryHeap::pop(self).map(|Reverse((key, item))| (item, key))
+    }
+
+    fn len(&self) -> usize {
+        BinaryHeap::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::grid::{Direction, Grid, Point};
+
+    // Cheapest path from the top left to the bottom right, entering each
+    // cell costing its digit
+    fn dijkstra<H: MinHeap<Key = u64>>(grid: &Grid<Vec<u8>>, mut heap: H) -> Option<u64> {
+        let index = |p: Point| p.y * grid.width() + p.x;
+        let mut distance = vec![u64::MAX; grid.len()];
+        distance[0] = 0;
+        heap.push(0, 0);
+        while let Some((i, d)) = heap.pop() {
+            if d > distance[i] {
+                continue;
+            }
+            let p = Point::new(i % grid.width(), i / grid.width());
+            if p == Point::new(grid.width() - 1, grid.height() - 1) {
+                return Some(d);
+            }
+            for direction in Direction::all() {
+                let Some(cell) = p.go(direction).and_then(|n| n.on(grid)) else {
+                    continue;
+                };
+                let next = d + *cell.value() as u64;
+                let j = index(cell.point());
+                if next < distance[j] {
+                    distance[j] = next;
+                    heap.push(j, next);
+                }
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_heaps_agree() {
+        let input = "\
+1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+        let grid: Grid<Vec<u8>> = input.parse().unwrap();
+        assert_eq!(dijkstra(&grid, BinaryHeap::new()), Some(40));
+        assert_eq!(dijkstra(&grid, IndexedHeap::new(grid.len())), Some(40));
+        assert_eq!(dijkstra(&grid, RadixHeap::new()), Some(40));
+    }
+}
@@ -0,0 +1,174 @@
+use super::MinHeap;
+
+const ABSENT: usize = usize::MAX;
+
+/// Binary min-heap holding each item at most once, tracking where every item
+/// sits so its key can be lowered in place.
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K> {
+    heap: Vec<usize>,
+    // Position of each item in `heap`, or ABSENT
+    position: Vec<usize>,
+    keys: Vec<Option<K>>,
+}
+
+impl<K: Copy + Ord> IndexedHeap<K> {
+    /// An empty heap sized for items `0..capacity`. Larger items still work
+    /// but grow the index.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            heap: Vec::new(),
+            position: vec![ABSENT; capacity],
+            keys: vec![None; capacity],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, item: usize) -> bool {
+        self.position.get(item).is_some_and(|&p| p != ABSENT)
+    }
+
+    /// The key of `item` if queued.
+    pub fn key(&self, item: usize) -> Option<K> {
+        self.keys.get(item).copied().flatten()
+    }
+
+    pub fn peek(&self) -> Option<(usize, K)> {
+        let &item = self.heap.first()?;
+        Some((item, self.keys[item].unwrap()))
+    }
+
+    /// Queue a new item, or lower the key of a queued one. Returns false if
+    /// `item` was already queued with a key no higher than `key`.
+    pub fn push(&mut self, item: usize, key: K) -> bool {
+        if item >= self.position.len() {
+            self.position.resize(item + 1, ABSENT);
+            self.keys.resize(item + 1, None);
+        }
+        if self.contains(item) {
+            return self.decrease_key(item, key);
+        }
+        self.keys[item] = Some(key);
+        self.position[item] = self.heap.len();
+        self.heap.push(item);
+        self.sift_up(self.heap.len() - 1);
+        true
+    }
+
+    /// Lower the key of a queued item, returning false if it isn't queued or
+    /// `key` isn't lower.
+    pub fn decrease_key(&mut self, item: usize, key: K) -> bool {
+        match self.key(item) {
+            Some(current) if key < current && self.contains(item) => {
+                self.keys[item] = Some(key);
+                self.sift_up(self.position[item]);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, K)> {
+        let top = *self.heap.first()?;
+        let last = self.heap.pop().unwrap();
+        if !self.heap.is_empty() {
+            self.heap[0] = last;
+            self.position[last] = 0;
+            self.sift_down(0);
+        }
+        self.position[top] = ABSENT;
+        Some((top, self.keys[top].take().unwrap()))
+    }
+
+    fn key_at(&self, i: usize) -> K {
+        self.keys[self.heap[i]].unwrap()
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.position[self.heap[i]] = i;
+        self.position[self.heap[j]] = j;
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.key_at(i) >= self.key_at(parent) {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let smallest = [2 * i + 1, 2 * i + 2]
+                .into_iter()
+                .filter(|&c| c < self.heap.len())
+                .fold(i, |best, c| {
+                    if self.key_at(c) < self.key_at(best) {
+                        c
+                    } else {
+                        best
+                    }
+                });
+            if smallest == i {
+                break;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+impl<K: Copy + Ord> MinHeap for IndexedHeap<K> {
+    type Key = K;
+
+    fn push(&mut self, item: usize, key: K) {
+        IndexedHeap::push(self, item, key);
+    }
+
+    fn pop(&mut self) -> Option<(usize, K)> {
+        IndexedHeap::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        IndexedHeap::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decrease_key() {
+        let mut heap = IndexedHeap::new(4);
+        assert!(heap.push(0, 50));
+        assert!(heap.push(1, 20));
+        assert!(heap.push(2, 30));
+        assert!(heap.push(7, 40));
+        assert_eq!(heap.len(), 4);
+
+        assert!(heap.push(0, 10));
+        assert!(!heap.push(2, 35));
+        assert!(!heap.decrease_key(5, 1));
+        assert_eq!(heap.key(2), Some(30));
+        assert_eq!(heap.peek(), Some((0, 10)));
+
+        let order: Vec<_> = std::iter::from_fn(|| heap.pop()).collect();
+        assert_eq!(order, vec![(0, 10), (1, 20), (2, 30), (7, 40)]);
+        assert!(!heap.contains(0));
+        // Popped items can be queued again
+        assert!(heap.push(0, 5));
+        assert_eq!(heap.pop(), Some((0, 5)));
+    }
+}
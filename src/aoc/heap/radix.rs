@@ -0,0 +1,115 @@
+use super::MinHeap;
+
+/// Radix heap for monotone `u64` keys: no key pushed may be lower than the
+/// last one popped, which always holds for Dijkstra with non-negative
+/// weights. Entries are bucketed by the highest bit where they differ from
+/// the last popped key, so each is only moved a few times.
+#[derive(Debug, Clone)]
+pub struct RadixHeap {
+    buckets: [Vec<(u64, usize)>; 65],
+    last: u64,
+    len: usize,
+}
+
+impl Default for RadixHeap {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| Vec::new()),
+            last: 0,
+            len: 0,
+        }
+    }
+}
+
+impl RadixHeap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn bucket(&self, key: u64) -> usize {
+        (u64::BITS - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    /// Panics if `key` is below the last popped key.
+    pub fn push(&mut self, item: usize, key: u64) {
+        assert!(
+            key >= self.last,
+            "Radix heap keys must not go below {}",
+            self.last
+        );
+        let bucket = self.bucket(key);
+        self.buckets[bucket].push((key, item));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, u64)> {
+        if self.buckets[0].is_empty() {
+            let i = self.buckets.iter().position(|b| !b.is_empty())?;
+            let entries = std::mem::take(&mut self.buckets[i]);
+            self.last = entries.iter().map(|&(key, _)| key).min().unwrap();
+            for (key, item) in entries {
+                let bucket = self.bucket(key);
+                self.buckets[bucket].push((key, item));
+            }
+        }
+        let (key, item) = self.buckets[0].pop()?;
+        self.len -= 1;
+        Some((item, key))
+    }
+}
+
+impl MinHeap for RadixHeap {
+    type Key = u64;
+
+    fn push(&mut self, item: usize, key: u64) {
+        RadixHeap::push(self, item, key);
+    }
+
+    fn pop(&mut self) -> Option<(usize, u64)> {
+        RadixHeap::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        RadixHeap::len(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_order() {
+        let mut heap = RadixHeap::new();
+        for (item, key) in [(0, 17), (1, 3), (2, 1 << 40), (3, 3), (4, 200)] {
+            heap.push(item, key);
+        }
+        let mut keys = Vec::new();
+        while let Some((item, key)) = heap.pop() {
+            keys.push(key);
+            if item == 1 {
+                // Monotone pushes between pops are fine
+                heap.push(5, 150);
+            }
+        }
+        assert_eq!(keys, vec![3, 3, 17, 150, 200, 1 << 40]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rejects_lower_keys() {
+        let mut heap = RadixHeap::new();
+        heap.push(0, 10);
+        heap.pop();
+        heap.push(1, 9);
+    }
+}
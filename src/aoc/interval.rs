@@ -0,0 +1,419 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Range, RangeInclusive, Sub},
+    str::FromStr,
+};
+
+use crate::quant::{CheckedAdd, NumConsts};
+
+mod map;
+
+pub use map::{IntervalMap, Rule, Unmatched};
+
+/// What `Interval` needs from its bounds. Implemented for every primitive
+/// integer.
+pub trait Bound:
+    Copy + Ord + NumConsts + CheckedAdd + Add<Output = Self> + Sub<Output = Self> + std::fmt::Debug
+{
+}
+
+impl<T> Bound for T where
+    T: Copy + Ord + NumConsts + CheckedAdd + Add<Output = T> + Sub<Output = T> + std::fmt::Debug
+{
+}
+
+/// Half-open interval `start..end`. Use `Interval::inclusive` for `a..=b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end: end.max(start),
+        }
+    }
+
+    /// `start..=last`. Panics if `last` is `T::MAX`, as the exclusive end
+    /// would be past it; see `try_inclusive`.
+    pub fn inclusive(start: T, last: T) -> Self {
+        Self::try_inclusive(start, last)
+            .expect("Interval can't include T::MAX, its exclusive end would overflow")
+    }
+
+    /// `start..=last`, or `None` if `last` is `T::MAX`.
+    pub fn try_inclusive(start: T, last: T) -> Option<Self> {
+        if last < start {
+            Some(Self::new(start, start))
+        } else {
+            Some(Self::new(start, last.checked_add(T::ONE)?))
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// Exclusive end.
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    /// Inclusive end, `None` if empty.
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::ONE)
+    }
+
+    pub fn len(&self) -> T {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let i = Self::new(self.start.max(other.start), self.end.min(other.end));
+        (!i.is_empty()).then_some(i)
+    }
+
+    /// The parts before and from `cut`, either of which may be empty.
+    pub fn split_at(&self, cut: T) -> (Option<Self>, Option<Self>) {
+        let cut = cut.clamp(self.start, self.end);
+        let below = Self::new(self.start, cut);
+        let above = Self::new(cut, self.end);
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    pub fn range(&self) -> Range<T> {
+        self.start..self.end
+    }
+}
+
+impl<T: Bound> From<Range<T>> for Interval<T> {
+    fn from(r: Range<T>) -> Self {
+        Self::new(r.start, r.end)
+    }
+}
+
+impl<T: Bound> From<RangeInclusive<T>> for Interval<T> {
+    fn from(r: RangeInclusive<T>) -> Self {
+        let (start, last) = r.into_inner();
+        Self::inclusive(start, last)
+    }
+}
+
+/// Parses the inclusive `a-b` form puzzle inputs use.
+impl<T: Bound + FromStr> FromStr for Interval<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Skip the first character so a leading minus sign isn't the separator
+        let split = s
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| c == '-')
+            .map(|(i, _)| i)
+            .ok_or_else(|| format!("Expected a-b, got {s}"))?;
+        let parse = |part: &str| {
+            part.trim()
+                .parse::<T>()
+                .map_err(|_| format!("Invalid bound {part} in {s}"))
+        };
+        Self::try_inclusive(parse(&s[..split])?, parse(&s[split + 1..])?)
+            .ok_or_else(|| format!("Upper bound of {s} is too large to include"))
+    }
+}
+
+impl<T: Bound + Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-adjacent intervals, so
+/// every operation is proportional to the number of intervals rather than
+/// their width.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Bound> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Total number of values in the set.
+    pub fn count(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |acc, interval| acc + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(Interval::start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().and_then(Interval::last)
+    }
+
+    // Index of the first interval ending at or after x
+    fn lower_index(&self, x: T) -> usize {
+        self.intervals.partition_point(|i| i.end < x)
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.intervals
+            .get(self.intervals.partition_point(|i| i.end <= x))
+            .is_some_and(|i| i.contains(x))
+    }
+
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        interval.is_empty()
+            || self
+                .intervals
+                .get(self.intervals.partition_point(|i| i.end <= interval.start))
+                .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    pub fn insert(&mut self, interval: impl Into<Interval<T>>) {
+        let interval = interval.into();
+        if interval.is_empty() {
+            return;
+        }
+        // Everything touching the new interval, including adjacent ones, is
+        // merged into it
+        let lo = self.lower_index(interval.start);
+        let hi = self.intervals.partition_point(|i| i.start <= interval.end);
+        let merged = self.intervals[lo..hi].iter().fold(interval, |acc, i| {
+            Interval::new(acc.start.min(i.start), acc.end.max(i.end))
+        });
+        self.intervals.splice(lo..hi, std::iter::once(merged));
+    }
+
+    pub fn remove(&mut self, interval: impl Into<Interval<T>>) {
+        let interval = interval.into();
+        if interval.is_empty() {
+            return;
+        }
+        let lo = self.intervals.partition_point(|i| i.end <= interval.start);
+        let hi = self.intervals.partition_point(|i| i.start < interval.end);
+        let remains: Vec<_> = self.intervals[lo..hi]
+            .iter()
+            .flat_map(|i| {
+                let (below, _) = i.split_at(interval.start);
+                let (_, above) = i.split_at(interval.end);
+                [below, above]
+            })
+            .flatten()
+            .collect();
+        self.intervals.splice(lo..hi, remains);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for &interval in &other.intervals {
+            out.insert(interval);
+        }
+        out
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(overlap) = a.intersection(&b) {
+                intervals.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for &interval in &other.intervals {
+            out.remove(interval);
+        }
+        out
+    }
+
+    /// The values below `cut` and the values from `cut` upwards.
+    pub fn split_at(&self, cut: T) -> (Self, Self) {
+        let mut below = Vec::new();
+        let mut above = Vec::new();
+        for interval in &self.intervals {
+            let (b, a) = interval.split_at(cut);
+            below.extend(b);
+            above.extend(a);
+        }
+        (Self { intervals: below }, Self { intervals: above })
+    }
+}
+
+impl<T: Bound, I: Into<Interval<T>>> FromIterator<I> for RangeSet<T> {
+    fn from_iter<It: IntoIterator<Item = I>>(iter: It) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+impl<T: Bound, I: Into<Interval<T>>> Extend<I> for RangeSet<T> {
+    fn extend<It: IntoIterator<Item = I>>(&mut self, iter: It) {
+        for interval in iter {
+            self.insert(interval);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RangeSet<T> {
+    type Item = &'a Interval<T>;
+
+    type IntoIter = std::slice::Iter<'a, Interval<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.intervals.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(ranges: &[RangeInclusive<i64>]) -> RangeSet<i64> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_interval() {
+        let i = Interval::inclusive(3, 7);
+        assert_eq!(i, Interval::from(3..8));
+        assert_eq!(i.len(), 5);
+        assert_eq!(i.last(), Some(7));
+        assert!(i.contains(7));
+        assert!(!i.contains(8));
+        assert_eq!(i.intersection(&(5..20).into()), Some(Interval::new(5, 8)));
+        assert_eq!(i.intersection(&(8..20).into()), None);
+        assert_eq!(
+            i.split_at(5),
+            (Some(Interval::new(3, 5)), Some(Interval::new(5, 8)))
+        );
+        assert_eq!(i.split_at(0), (None, Some(i)));
+        assert!(Interval::inclusive(5, 4).is_empty());
+
+        // The top of the type can't be included
+        assert_eq!(Interval::try_inclusive(250u8, u8::MAX), None);
+        assert_eq!(
+            Interval::try_inclusive(250u8, 254),
+            Some(Interval::new(250, u8::MAX))
+        );
+        assert!("0-18446744073709551615".parse::<Interval<u64>>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "can't include T::MAX")]
+    fn test_inclusive_max() {
+        Interval::inclusive(0, u64::MAX);
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        let s = set(&[3..=5, 10..=14, 16..=20, 12..=18]);
+        assert_eq!(
+            s.intervals(),
+            &[Interval::inclusive(3, 5), Interval::inclusive(10, 20)]
+        );
+        assert_eq!(s.count(), 14);
+
+        // Adjacent intervals merge too
+        let s = set(&[1..=2, 3..=4]);
+        assert_eq!(s.intervals(), &[Interval::inclusive(1, 4)]);
+
+        assert!(s.contains(1));
+        assert!(s.contains(4));
+        assert!(!s.contains(5));
+        assert!(s.contains_interval(&Interval::inclusive(2, 3)));
+        assert!(!s.contains_interval(&Interval::inclusive(2, 5)));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..=9, 20..=29]);
+        let b = set(&[5..=24]);
+
+        assert_eq!(a.union(&b), set(&[0..=29]));
+        assert_eq!(a.intersection(&b), set(&[5..=9, 20..=24]));
+        assert_eq!(a.difference(&b), set(&[0..=4, 25..=29]));
+        assert_eq!(b.difference(&a), set(&[10..=19]));
+        assert_eq!(a.difference(&a), RangeSet::new());
+
+        let (below, above) = a.split_at(25);
+        assert_eq!(below, set(&[0..=9, 20..=24]));
+        assert_eq!(above, set(&[25..=29]));
+        assert_eq!(a.min(), Some(0));
+        assert_eq!(a.max(), Some(29));
+    }
+
+    #[test]
+    fn test_parse() {
+        let interval: Interval<usize> = "11-22".parse().unwrap();
+        assert_eq!(interval, Interval::inclusive(11, 22));
+        let negative: Interval<i64> = "-5--3".parse().unwrap();
+        assert_eq!(negative, Interval::inclusive(-5, -3));
+        assert!("11".parse::<Interval<usize>>().is_err());
+    }
+
+    #[test]
+    fn test_wide_ranges() {
+        let s: RangeSet<u64> = [0..=u64::MAX / 2, 10..=u64::MAX / 4].into_iter().collect();
+        assert_eq!(s.count(), u64::MAX / 2 + 1);
+    }
+}
@@ -0,0 +1,256 @@
+use super::{Bound, Interval, RangeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unmatched {
+    PassThrough,
+    Drop,
+}
+
+/// Moves the values in `source` so that `source.start()` lands on `dest`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule<T> {
+    pub source: Interval<T>,
+    pub dest: T,
+}
+
+impl<T: Bound> Rule<T> {
+    fn apply(&self, x: T) -> T {
+        self.dest + (x - self.source.start())
+    }
+
+    fn apply_interval(&self, interval: &Interval<T>) -> Interval<T> {
+        Interval::new(self.apply(interval.start()), self.apply(interval.end()))
+    }
+
+    fn image(&self) -> Interval<T> {
+        self.apply_interval(&self.source)
+    }
+
+    // The part of the source that maps onto `target`, which must lie within
+    // the image
+    fn preimage(&self, target: &Interval<T>) -> Interval<T> {
+        let start = self.source.start() + (target.start() - self.dest);
+        Interval::new(start, start + target.len())
+    }
+}
+
+/// A piecewise translation of values: each rule shifts one source interval,
+/// and values outside every rule either pass through unchanged or are
+/// dropped. Rule sources never overlap.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalMap<T> {
+    rules: Vec<Rule<T>>,
+    unmatched: Unmatched,
+}
+
+impl<T: Bound> IntervalMap<T> {
+    pub fn new(unmatched: Unmatched) -> Self {
+        Self {
+            rules: Vec::new(),
+            unmatched,
+        }
+    }
+
+    pub fn rules(&self) -> &[Rule<T>] {
+        &self.rules
+    }
+
+    pub fn unmatched(&self) -> Unmatched {
+        self.unmatched
+    }
+
+    pub fn add_rule(&mut self, source: impl Into<Interval<T>>, dest: T) {
+        let source = source.into();
+        if source.is_empty() {
+            return;
+        }
+        let i = self
+            .rules
+            .partition_point(|rule| rule.source.start() < source.start());
+        let clashes = |j: usize| {
+            self.rules
+                .get(j)
+                .is_some_and(|rule: &Rule<T>| rule.source.overlaps(&source))
+        };
+        assert!(
+            !clashes(i) && (i == 0 || !clashes(i - 1)),
+            "Interval map rules must not overlap"
+        );
+        self.rules.insert(i, Rule { source, dest });
+    }
+
+    pub fn with_rule(mut self, source: impl Into<Interval<T>>, dest: T) -> Self {
+        self.add_rule(source, dest);
+        self
+    }
+
+    pub fn map_value(&self, x: T) -> Option<T> {
+        let i = self.rules.partition_point(|rule| rule.source.end() <= x);
+        match self.rules.get(i) {
+            Some(rule) if rule.source.contains(x) => Some(rule.apply(x)),
+            _ => (self.unmatched == Unmatched::PassThrough).then_some(x),
+        }
+    }
+
+    /// Splits `interval` at the rule boundaries and maps each piece.
+    pub fn map_interval(&self, interval: &Interval<T>) -> Vec<Interval<T>> {
+        let mut out = Vec::new();
+        let mut rest = Some(*interval).filter(|i| !i.is_empty());
+        let first = self
+            .rules
+            .partition_point(|rule| rule.source.end() <= interval.start());
+
+        for rule in &self.rules[first..] {
+            let Some(current) = rest else {
+                break;
+            };
+            let (gap, remainder) = current.split_at(rule.source.start());
+            if let Some(gap) = gap
+                && self.unmatched == Unmatched::PassThrough
+            {
+                out.push(gap);
+            }
+            let Some(remainder) = remainder else {
+                rest = None;
+                break;
+            };
+            let (matched, after) = remainder.split_at(rule.source.end());
+            if let Some(matched) = matched {
+                out.push(rule.apply_interval(&matched));
+            }
+            rest = after;
+        }
+
+        if let Some(current) = rest
+            && self.unmatched == Unmatched::PassThrough
+        {
+            out.push(current);
+        }
+        out
+    }
+
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        set.iter()
+            .flat_map(|interval| self.map_interval(interval))
+            .collect()
+    }
+
+    /// A single map equivalent to applying `self` and then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        let unmatched = if self.unmatched == Unmatched::Drop || next.unmatched == Unmatched::Drop {
+            Unmatched::Drop
+        } else {
+            Unmatched::PassThrough
+        };
+        let mut composed = Self::new(unmatched);
+
+        // Values moved by our rules, then whatever `next` does to them
+        for rule in &self.rules {
+            let image = rule.image();
+            let mut unhandled = RangeSet::from_iter([image]);
+            for next_rule in &next.rules {
+                if let Some(overlap) = next_rule.source.intersection(&image) {
+                    let source = rule.preimage(&overlap);
+                    composed.add_rule(source, next_rule.apply(overlap.start()));
+                    unhandled.remove(overlap);
+                }
+            }
+            if next.unmatched == Unmatched::PassThrough {
+                for part in &unhandled {
+                    composed.add_rule(rule.preimage(part), part.start());
+                }
+            }
+        }
+
+        // Values we let through untouched, which `next` may still move
+        if self.unmatched == Unmatched::PassThrough {
+            let ours: RangeSet<T> = self.rules.iter().map(|rule| rule.source).collect();
+            for next_rule in &next.rules {
+                let free = RangeSet::from_iter([next_rule.source]).difference(&ours);
+                for part in &free {
+                    composed.add_rule(*part, next_rule.apply(part.start()));
+                }
+            }
+        }
+
+        composed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The seed-to-soil and soil-to-fertilizer maps from the classic example
+    fn seed_to_soil() -> IntervalMap<u64> {
+        IntervalMap::new(Unmatched::PassThrough)
+            .with_rule(98..100, 50)
+            .with_rule(50..98, 52)
+    }
+
+    fn soil_to_fertilizer() -> IntervalMap<u64> {
+        IntervalMap::new(Unmatched::PassThrough)
+            .with_rule(15..52, 0)
+            .with_rule(52..54, 37)
+            .with_rule(0..15, 39)
+    }
+
+    #[test]
+    fn test_map_value() {
+        let map = seed_to_soil();
+        assert_eq!(map.map_value(79), Some(81));
+        assert_eq!(map.map_value(14), Some(14));
+        assert_eq!(map.map_value(99), Some(51));
+
+        let dropping = IntervalMap::new(Unmatched::Drop).with_rule(98..100, 50u64);
+        assert_eq!(dropping.map_value(14), None);
+        assert_eq!(dropping.map_value(98), Some(50));
+    }
+
+    #[test]
+    fn test_map_interval_splits() {
+        let map = seed_to_soil();
+        assert_eq!(
+            map.map_interval(&Interval::new(40, 105)),
+            vec![
+                Interval::new(40, 50),
+                Interval::new(52, 100),
+                Interval::new(50, 52),
+                Interval::new(100, 105),
+            ]
+        );
+
+        let set = map.map_set(&RangeSet::from_iter([Interval::new(40, 105u64)]));
+        assert_eq!(set.intervals(), &[Interval::new(40, 105)]);
+
+        let dropping = IntervalMap::new(Unmatched::Drop).with_rule(50..60, 0u64);
+        assert_eq!(
+            dropping.map_interval(&Interval::new(40, 55)),
+            vec![Interval::new(0, 5)]
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        let composed = seed_to_soil().then(&soil_to_fertilizer());
+        for x in 0..120 {
+            let expected = seed_to_soil()
+                .map_value(x)
+                .and_then(|y| soil_to_fertilizer().map_value(y));
+            assert_eq!(composed.map_value(x), expected, "mapping {x}");
+        }
+
+        let dropping = IntervalMap::new(Unmatched::Drop).with_rule(0..60, 10u64);
+        for (a, b) in [
+            (seed_to_soil(), dropping.clone()),
+            (dropping.clone(), seed_to_soil()),
+            (dropping.clone(), dropping.clone()),
+        ] {
+            let composed = a.then(&b);
+            for x in 0..120 {
+                let expected = a.map_value(x).and_then(|y| b.map_value(y));
+                assert_eq!(composed.map_value(x), expected, "mapping {x}");
+            }
+        }
+    }
+}
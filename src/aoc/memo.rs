@@ -0,0 +1,344 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// A cache for recursive functions, so they don't need to thread a
+/// `HashMap` by hand. With a capacity limit the oldest entries are dropped
+/// first. Named memos print their hit and miss counts when dropped in debug
+/// builds.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    // Insertion order, only kept when there is a capacity limit
+    order: VecDeque<K>,
+    capacity: Option<usize>,
+    name: Option<&'static str>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: None,
+            name: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `capacity` results.
+    pub fn with_capacity_limit(capacity: usize) -> Self {
+        let mut memo = Self::default();
+        memo.capacity = Some(capacity);
+        memo
+    }
+
+    /// Label the statistics printed in debug builds.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if let Some(capacity) = self.capacity {
+            if capacity == 0 {
+                return;
+            }
+            // Overwriting keeps the key's original place in the queue
+            if !self.cache.contains_key(&key) {
+                while self.cache.len() >= capacity
+                    && let Some(oldest) = self.order.pop_front()
+                {
+                    self.cache.remove(&oldest);
+                }
+                self.order.push_back(key.clone());
+            }
+        }
+        self.cache.insert(key, value);
+    }
+
+    /// The cached value for `key`, or compute it with `f`. `f` gets the memo
+    /// back so it can recurse through it.
+    pub fn get_or_insert_with<F>(&mut self, key: K, f: F) -> V
+    where
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = f(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        if cfg!(debug_assertions)
+            && let Some(name) = self.name
+        {
+            eprintln!(
+                "memo {name}: {} hits, {} misses, {} cached",
+                self.hits,
+                self.misses,
+                self.cache.len()
+            );
+        }
+    }
+}
+
+/// A recursive closure with its results cached. The closure gets a
+/// function to recurse through as its first argument.
+pub struct Memoized<K, V, F> {
+    memo: Memo<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memoized<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self::with_memo(Memo::new(), f)
+    }
+
+    /// Use a memo set up with a capacity limit or name.
+    pub fn with_memo(memo: Memo<K, V>, f: F) -> Self {
+        Self { memo, f }
+    }
+
+    pub fn call(&mut self, key: K) -> V {
+        Self::call_with(&mut self.memo, &self.f, key)
+    }
+
+    fn call_with(memo: &mut Memo<K, V>, f: &F, key: K) -> V {
+        memo.get_or_insert_with(key.clone(), |memo| {
+            f(&mut |k| Self::call_with(memo, f, k), key)
+        })
+    }
+
+    pub fn memo(&self) -> &Memo<K, V> {
+        &self.memo
+    }
+}
+
+/// Wrap a function so each top level call caches its recursive calls. The
+/// cache key defaults to a tuple of the arguments, which must then be
+/// `Clone + Hash + Eq`. Give a `where key` clause to key on something else, such
+/// as the length of a borrowed slice:
+///
+/// ```
+/// aoc2025::memoize! {
+///     fn max_concat_digits(xs: &[usize], count: u32) -> usize
+///     where key (xs.len(), count): (usize, u32)
+///     {
+///         if count == 0 {
+///             return 0;
+///         }
+///         let last = xs.len() - count as usize;
+///         let (i, &max) = xs[..=last]
+///             .iter()
+///             .enumerate()
+///             .rev()
+///             .max_by_key(|&(_, x)| x)
+///             .unwrap();
+///         10usize.pow(count - 1) * max + max_concat_digits(&xs[i + 1..], count - 1)
+///     }
+/// }
+///
+/// assert_eq!(max_concat_digits(&[8, 1, 8, 1, 9, 1, 1, 1, 2, 1, 1, 1], 2), 92);
+/// ```
+///
+/// Inside the body the function's own name refers to the cached version.
+/// Start with `stats` to name the memo after the function, so debug builds
+/// print its hit and miss counts after each top level call.
+#[macro_export]
+macro_rules! memoize {
+    (
+        stats
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+        where key ($($key:expr),* $(,)?): $key_ty:ty
+        $body:block
+    ) => {
+        $crate::memoize! {
+            @with [$crate::aoc::memo::Memo::new().named(stringify!($name))]
+            $(#[$attr])*
+            $vis fn $name($($arg: $ty),*) -> $ret
+            where key ($($key),*): $key_ty
+            $body
+        }
+    };
+    (
+        stats
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+        $body:block
+    ) => {
+        $crate::memoize! {
+            stats
+            $(#[$attr])*
+            $vis fn $name($($arg: $ty),*) -> $ret
+            where key ($($arg.clone()),*): ($($ty,)*)
+            $body
+        }
+    };
+    (
+        @with [$memo:expr]
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+        where key ($($key:expr),* $(,)?): $key_ty:ty
+        $body:block
+    ) => {
+        $(#[$attr])*
+        $vis fn $name($($arg: $ty),*) -> $ret {
+            fn inner(
+                memo: &mut $crate::aoc::memo::Memo<$key_ty, $ret>,
+                $($arg: $ty),*
+            ) -> $ret {
+                memo.get_or_insert_with(($($key,)*), |memo| {
+                    #[allow(unused_mut)]
+                    let mut $name = |$($arg: $ty),*| inner(memo, $($arg),*);
+                    $body
+                })
+            }
+            let mut memo = $memo;
+            inner(&mut memo, $($arg),*)
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+        where key ($($key:expr),* $(,)?): $key_ty:ty
+        $body:block
+    ) => {
+        $crate::memoize! {
+            @with [$crate::aoc::memo::Memo::new()]
+            $(#[$attr])*
+            $vis fn $name($($arg: $ty),*) -> $ret
+            where key ($($key),*): $key_ty
+            $body
+        }
+    };
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty
+        $body:block
+    ) => {
+        $crate::memoize! {
+            $(#[$attr])*
+            $vis fn $name($($arg: $ty),*) -> $ret
+            where key ($($arg.clone()),*): ($($ty,)*)
+            $body
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    crate::memoize! {
+        fn count_paths(width: u64, height: u64) -> u64 {
+            if width == 0 || height == 0 {
+                1
+            } else {
+                count_paths(width - 1, height) + count_paths(width, height - 1)
+            }
+        }
+    }
+
+    crate::memoize! {
+        stats
+        fn fib(n: u64) -> u64 {
+            if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+        }
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(count_paths(2, 2), 6);
+        // Far too slow without the cache
+        assert_eq!(count_paths(30, 30), 118264581564861424);
+        assert_eq!(fib(90), 2880067194370816120);
+    }
+
+    #[test]
+    fn test_memoized_closure() {
+        let mut fib = Memoized::new(|fib, n: u64| if n < 2 { n } else { fib(n - 1) + fib(n - 2) });
+        assert_eq!(fib.call(90), 2880067194370816120);
+        assert_eq!(fib.memo().misses(), 91);
+        assert_eq!(fib.call(90), 2880067194370816120);
+        assert_eq!(fib.memo().hits(), 89);
+    }
+
+    #[test]
+    fn test_capacity_limit() {
+        let mut memo = Memo::with_capacity_limit(2);
+        for key in 0..5 {
+            memo.get_or_insert_with(key, |_| key * 10);
+        }
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.get(&4), Some(&40));
+        assert_eq!(memo.get(&1), None);
+        assert_eq!(memo.get_or_insert_with(3, |_| unreachable!()), 30);
+        assert_eq!((memo.hits(), memo.misses()), (1, 5));
+    }
+
+    #[test]
+    fn test_reinsert() {
+        let mut memo = Memo::with_capacity_limit(2);
+        for value in 0..5 {
+            memo.insert(1, value);
+        }
+        assert_eq!(memo.order.len(), 1);
+        assert_eq!(memo.get(&1), Some(&4));
+
+        memo.insert(2, 20);
+        assert_eq!(memo.len(), 2);
+        assert_eq!(memo.get(&1), Some(&4));
+        memo.insert(3, 30);
+        assert_eq!(memo.get(&1), None);
+        assert_eq!(memo.get(&2), Some(&20));
+        assert_eq!(memo.order, [2, 3]);
+    }
+}
@@ -0,0 +1,187 @@
+use super::dsu::Dsu;
+
+/// One edge considered by `Kruskal`, with the number of components left
+/// after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Step<W> {
+    pub a: usize,
+    pub b: usize,
+    pub weight: W,
+    /// False if `a` and `b` were already connected.
+    pub merged: bool,
+    pub components: usize,
+}
+
+/// Kruskal's algorithm as an iterator over every edge in increasing weight
+/// order, ties broken by endpoints, so puzzles can stop after some number of
+/// edges or merges and inspect the components.
+#[derive(Debug, Clone)]
+pub struct Kruskal<W> {
+    edges: Vec<(usize, usize, W)>,
+    next: usize,
+    dsu: Dsu,
+}
+
+impl<W: Copy + Ord> Kruskal<W> {
+    /// `edges` are `(a, b, weight)` between nodes in `0..n`.
+    pub fn new(n: usize, mut edges: Vec<(usize, usize, W)>) -> Self {
+        edges.sort_unstable_by_key(|&(a, b, w)| (w, a, b));
+        Self {
+            edges,
+            next: 0,
+            dsu: Dsu::new(n),
+        }
+    }
+
+    pub fn dsu(&mut self) -> &mut Dsu {
+        &mut self.dsu
+    }
+
+    pub fn components(&self) -> usize {
+        self.dsu.components()
+    }
+
+    /// The size of every component, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.dsu.component_sizes()
+    }
+
+    /// Consider edges until one joins two components.
+    pub fn next_merge(&mut self) -> Option<Step<W>> {
+        self.find(|step| step.merged)
+    }
+
+    /// Merge until everything is connected, returning the edge that joined
+    /// the final two components. `None` if the graph is disconnected or was
+    /// already connected.
+    pub fn connect(&mut self) -> Option<Step<W>> {
+        self.find(|step| step.merged && step.components == 1)
+    }
+}
+
+impl<W: Copy + Ord> Iterator for Kruskal<W> {
+    type Item = Step<W>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &(a, b, weight) = self.edges.get(self.next)?;
+        self.next += 1;
+        let merged = self.dsu.union(a, b);
+        Some(Step {
+            a,
+            b,
+            weight,
+            merged,
+            components: self.dsu.components(),
+        })
+    }
+}
+
+/// The edges of a minimum spanning forest, in the order Kruskal adds them.
+pub fn spanning_forest<W: Copy + Ord>(
+    n: usize,
+    edges: Vec<(usize, usize, W)>,
+) -> Vec<(usize, usize, W)> {
+    Kruskal::new(n, edges)
+        .filter(|step| step.merged)
+        .map(|step| (step.a, step.b, step.weight))
+        .collect()
+}
+
+/// Prim's algorithm on the complete graph over `0..n` with `distance(a, b)`
+/// as weights, in O(n^2) without building the edge list. Returns the tree's
+/// edges as `(in tree, added, weight)` in the order they are added, starting
+/// from node 0.
+pub fn prim<W, F>(n: usize, distance: F) -> Vec<(usize, usize, W)>
+where
+    W: Copy + Ord,
+    F: Fn(usize, usize) -> W,
+{
+    let mut edges = Vec::with_capacity(n.saturating_sub(1));
+    if n == 0 {
+        return edges;
+    }
+    let mut in_tree = vec![false; n];
+    // Cheapest known link from the tree to each node
+    let mut best: Vec<Option<(W, usize)>> = vec![None; n];
+    let mut current = 0;
+    in_tree[0] = true;
+    for _ in 1..n {
+        for other in 0..n {
+            if in_tree[other] {
+                continue;
+            }
+            let d = distance(current, other);
+            if best[other].is_none_or(|(w, from)| (d, current) < (w, from)) {
+                best[other] = Some((d, current));
+            }
+        }
+        let (next, (weight, from)) = (0..n)
+            .filter(|&i| !in_tree[i])
+            .map(|i| (i, best[i].unwrap()))
+            .min_by_key(|&(i, (w, from))| (w, from, i))
+            .unwrap();
+        in_tree[next] = true;
+        edges.push((from, next, weight));
+        current = next;
+    }
+    edges
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn points() -> Vec<[i64; 2]> {
+        vec![[0, 0], [1, 0], [10, 0], [10, 2], [5, 5], [0, 1]]
+    }
+
+    fn distance(a: &[i64; 2], b: &[i64; 2]) -> i64 {
+        (a[0] - b[0]).pow(2) + (a[1] - b[1]).pow(2)
+    }
+
+    fn all_pairs() -> Vec<(usize, usize, i64)> {
+        let points = points();
+        let mut edges = Vec::new();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                edges.push((i, j, distance(&points[i], &points[j])));
+            }
+        }
+        edges
+    }
+
+    #[test]
+    fn test_kruskal_steps() {
+        let mut kruskal = Kruskal::new(6, all_pairs());
+        let steps: Vec<_> = kruskal.by_ref().take(4).collect();
+        assert_eq!(
+            steps
+                .iter()
+                .map(|s| (s.a, s.b, s.merged))
+                .collect::<Vec<_>>(),
+            vec![(0, 1, true), (0, 5, true), (1, 5, false), (2, 3, true)]
+        );
+        assert_eq!(kruskal.components(), 3);
+        assert_eq!(kruskal.component_sizes(), vec![3, 2, 1]);
+
+        let last = kruskal.connect().unwrap();
+        // (1, 4) and (4, 5) tie at 41, so the lower endpoints win
+        assert_eq!((last.a, last.b, last.weight), (1, 4, 41));
+        assert_eq!(kruskal.components(), 1);
+        assert_eq!(kruskal.next_merge(), None);
+    }
+
+    #[test]
+    fn test_prim_matches_kruskal() {
+        let points = points();
+        let forest = spanning_forest(6, all_pairs());
+        let tree = prim(6, |a, b| distance(&points[a], &points[b]));
+        assert_eq!(tree.len(), 5);
+        let total = |edges: &[(usize, usize, i64)]| edges.iter().map(|e| e.2).sum::<i64>();
+        assert_eq!(total(&tree), total(&forest));
+        assert_eq!(total(&tree), 1 + 1 + 4 + 34 + 41);
+
+        // Two components stay apart
+        assert_eq!(spanning_forest(4, vec![(0, 1, 3), (2, 3, 1)]).len(), 2);
+    }
+}
@@ -0,0 +1,253 @@
+use std::{collections::HashMap, hash::Hash};
+
+mod stepper;
+
+pub use stepper::Stepper;
+
+type Callback<'a, S> = Box<dyn FnMut(usize, &S) + 'a>;
+
+// Recorded states and how to copy new ones, set by `record_history`
+type History<S> = (Vec<S>, fn(&S) -> S);
+
+/// Where a sequence of states starts repeating: the state after `start`
+/// steps comes back every `period` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+/// Drives a simulation from an initial state with a step function, counting
+/// steps and optionally recording every state and calling back after each
+/// step.
+pub struct Sim<'a, S, F> {
+    state: S,
+    step: F,
+    steps: usize,
+    history: Option<History<S>>,
+    callbacks: Vec<Callback<'a, S>>,
+}
+
+impl<'a, S, F> Sim<'a, S, F>
+where
+    F: FnMut(&S) -> S,
+{
+    pub fn new(initial: S, step: F) -> Self {
+        Self {
+            state: initial,
+            step,
+            steps: 0,
+            history: None,
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Call `callback` with the step number and new state after every step.
+    pub fn on_step<C: FnMut(usize, &S) + 'a>(mut self, callback: C) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Steps taken so far, including any skipped by cycle detection.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Every state so far starting with the initial one, if recording.
+    pub fn history(&self) -> Option<&[S]> {
+        self.history.as_ref().map(|(states, _)| states.as_slice())
+    }
+
+    fn advance(&mut self, next: S) {
+        if let Some((states, copy)) = &mut self.history {
+            states.push(copy(&next));
+        }
+        self.state = next;
+        self.steps += 1;
+        for callback in &mut self.callbacks {
+            callback(self.steps, &self.state);
+        }
+    }
+
+    pub fn step(&mut self) -> &S {
+        let next = (self.step)(&self.state);
+        self.advance(next);
+        &self.state
+    }
+
+    /// Take `n` steps one at a time. See `run_n` to skip cycles.
+    pub fn step_n(&mut self, n: usize) -> &S {
+        for _ in 0..n {
+            self.step();
+        }
+        &self.state
+    }
+
+    /// Step until `pred` holds, checking the current state first. Returns
+    /// the number of steps taken.
+    pub fn run_until<P: FnMut(&S) -> bool>(&mut self, mut pred: P) -> usize {
+        let start = self.steps;
+        while !pred(&self.state) {
+            self.step();
+        }
+        self.steps - start
+    }
+}
+
+impl<S: PartialEq, F: FnMut(&S) -> S> Sim<'_, S, F> {
+    /// Step until a step leaves the state unchanged, returning the number of
+    /// steps that changed it.
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.steps;
+        loop {
+            let next = (self.step)(&self.state);
+            if next == self.state {
+                return self.steps - start;
+            }
+            self.advance(next);
+        }
+    }
+}
+
+impl<S: Clone, F: FnMut(&S) -> S> Sim<'_, S, F> {
+    /// Record every state from the current one on.
+    pub fn record_history(mut self) -> Self {
+        self.history = Some((vec![self.state.clone()], S::clone));
+        self
+    }
+}
+
+impl<S: Clone + Hash + Eq, F: FnMut(&S) -> S> Sim<'_, S, F> {
+    /// Take `n` steps, jumping ahead once the states start repeating, so
+    /// huge step counts are fine. Callbacks and history only see the steps
+    /// actually simulated. Returns the cycle if one was found.
+    pub fn run_n(&mut self, n: usize) -> Option<Cycle> {
+        let target = self.steps + n;
+        let mut seen: HashMap<S, usize> = HashMap::new();
+        let mut states: Vec<S> = Vec::new();
+        while self.steps < target {
+            if let Some(&first) = seen.get(&self.state) {
+                let cycle = Cycle {
+                    start: first,
+                    period: self.steps - first,
+                };
+                let offset = (target - first) % cycle.period;
+                let base = states.len() - cycle.period;
+                self.state = states[base + offset].clone();
+                self.steps = target;
+                return Some(cycle);
+            }
+            seen.insert(self.state.clone(), self.steps);
+            states.push(self.state.clone());
+            self.step();
+        }
+        None
+    }
+}
+
+/// The first repeat in the states from `initial`, by Brent's algorithm in
+/// constant memory. Only finishes if the states do repeat.
+pub fn detect_cycle<S, F>(initial: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    // Find the period by racing a hare ahead of a tortoise that teleports
+    // to it at powers of two
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Then the start, walking two pointers a period apart
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let mut sim = Sim::new(1u64, |x| x * 3).record_history();
+        assert_eq!(sim.run_until(|&x| x > 100), 5);
+        assert_eq!(*sim.state(), 243);
+        sim.step_n(2);
+        assert_eq!(sim.steps(), 7);
+        assert_eq!(sim.history().unwrap(), &[1, 3, 9, 27, 81, 243, 729, 2187]);
+
+        // Halve until it stops changing
+        let mut sim = Sim::new(1000u32, |x| x / 2);
+        assert_eq!(sim.run_until_stable(), 10);
+        assert_eq!(*sim.state(), 0);
+    }
+
+    #[test]
+    fn test_callbacks() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let log = seen.clone();
+        let mut sim = Sim::new(0, |x| x + 2).on_step(move |i, &x| log.borrow_mut().push((i, x)));
+        sim.step_n(3);
+        assert_eq!(*seen.borrow(), vec![(1, 2), (2, 4), (3, 6)]);
+    }
+
+    #[test]
+    fn test_cycles() {
+        // Enters a cycle of length 6 after 2 steps
+        let step = |&x: &u32| if x < 2 { x + 1 } else { (x - 2 + 1) % 6 + 2 };
+        assert_eq!(
+            detect_cycle(0, step),
+            Cycle {
+                start: 2,
+                period: 6
+            }
+        );
+
+        let mut sim = Sim::new(0, step);
+        let cycle = sim.run_n(1_000_000_000_000);
+        assert_eq!(
+            cycle,
+            Some(Cycle {
+                start: 2,
+                period: 6
+            })
+        );
+        assert_eq!(sim.steps(), 1_000_000_000_000);
+        let expected = (1_000_000_000_000u64 - 2) % 6 + 2;
+        assert_eq!(*sim.state() as u64, expected);
+
+        // Too few steps to repeat
+        let mut short = Sim::new(0, step);
+        assert_eq!(short.run_n(5), None);
+        assert_eq!(*short.state(), 5);
+    }
+}
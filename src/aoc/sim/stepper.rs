@@ -0,0 +1,219 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, Write},
+};
+
+use super::Sim;
+
+type Breakpoint<'a, S> = (String, Box<dyn Fn(&S) -> bool + 'a>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Paused,
+    Skip(usize),
+    ToBreakpoint,
+    Detached,
+}
+
+const HELP: &str = "\
+commands:
+  n, next          take one step
+  c, continue N    take N steps, then pause again
+  r, run           run until a breakpoint is hit
+  d, dump PATH     write the state to PATH
+  detach           stop pausing
+  h, help          show this";
+
+/// Interactive debugger for a `Sim`, pausing after each step to print the
+/// state and read commands.
+pub struct Stepper<'a, S> {
+    input: Box<dyn BufRead + 'a>,
+    output: Box<dyn Write + 'a>,
+    breakpoints: Vec<Breakpoint<'a, S>>,
+    mode: Mode,
+}
+
+impl<'a, S: Display + 'a> Stepper<'a, S> {
+    pub fn new<R: BufRead + 'a, W: Write + 'a>(input: R, output: W) -> Self {
+        Self {
+            input: Box::new(input),
+            output: Box::new(output),
+            breakpoints: Vec::new(),
+            mode: Mode::Paused,
+        }
+    }
+
+    /// Read from stdin and print to stdout.
+    pub fn stdio() -> Self {
+        Self::new(io::stdin().lock(), io::stdout())
+    }
+
+    /// A named predicate for the `run` command to stop at.
+    pub fn breakpoint<P: Fn(&S) -> bool + 'a>(mut self, name: &str, pred: P) -> Self {
+        self.breakpoints.push((name.to_owned(), Box::new(pred)));
+        self
+    }
+
+    pub fn attach<F: FnMut(&S) -> S>(mut self, sim: Sim<'a, S, F>) -> Sim<'a, S, F> {
+        sim.on_step(move |step, state| self.after_step(step, state))
+    }
+
+    fn after_step(&mut self, step: usize, state: &S) {
+        match self.mode {
+            Mode::Detached => return,
+            Mode::Skip(n) if n > 1 => {
+                self.mode = Mode::Skip(n - 1);
+                return;
+            }
+            Mode::ToBreakpoint => {
+                let Some((name, _)) = self.breakpoints.iter().find(|(_, pred)| pred(state)) else {
+                    return;
+                };
+                let _ = writeln!(self.output, "breakpoint {name} hit");
+            }
+            _ => {}
+        }
+        self.mode = Mode::Paused;
+        // Nothing useful to do if the output is gone, so errors are ignored
+        let _ = self.prompt(step, state);
+    }
+
+    fn prompt(&mut self, step: usize, state: &S) -> io::Result<()> {
+        writeln!(self.output, "step {step}:\n{state}")?;
+        loop {
+            write!(self.output, "> ")?;
+            self.output.flush()?;
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                self.mode = Mode::Detached;
+                return Ok(());
+            }
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (None | Some("n" | "next"), _) => return Ok(()),
+                (Some("c" | "continue"), n) => match n.map(str::parse::<usize>) {
+                    Some(Ok(n)) if n > 0 => {
+                        self.mode = Mode::Skip(n);
+                        return Ok(());
+                    }
+                    _ => writeln!(self.output, "usage: continue N")?,
+                },
+                (Some("r" | "run"), _) => {
+                    if self.breakpoints.is_empty() {
+                        writeln!(self.output, "no breakpoints, running to the end")?;
+                    }
+                    self.mode = Mode::ToBreakpoint;
+                    return Ok(());
+                }
+                (Some("d" | "dump"), Some(path)) => {
+                    match std::fs::write(path, format!("{state}\n")) {
+                        Ok(()) => writeln!(self.output, "wrote step {step} to {path}")?,
+                        Err(e) => writeln!(self.output, "could not write {path}: {e}")?,
+                    }
+                }
+                (Some("detach"), _) => {
+                    self.mode = Mode::Detached;
+                    return Ok(());
+                }
+                (Some("h" | "help"), _) => writeln!(self.output, "{HELP}")?,
+                (Some(other), _) => writeln!(self.output, "unknown command {other}, try help")?,
+            }
+        }
+    }
+}
+
+impl<'a, S: Display + 'a, F: FnMut(&S) -> S> Sim<'a, S, F> {
+    /// Attach a `Stepper` on stdin and stdout if `step` is set, normally from
+    /// `Cli::step`.
+    pub fn stepped(self, step: bool) -> Self {
+        self.stepped_with(step, Stepper::stdio)
+    }
+
+    /// Attach the stepper `make` builds, such as one with breakpoints, if
+    /// `step` is set.
+    pub fn stepped_with<M: FnOnce() -> Stepper<'a, S>>(self, step: bool, make: M) -> Self {
+        if step { make().attach(self) } else { self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+
+    // Step headers, without the prompts that precede them when there is no
+    // terminal echoing the commands
+    fn headers(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .map(|l| l.trim_start_matches("> "))
+            .filter(|l| l.starts_with("step"))
+            .collect()
+    }
+
+    fn session(commands: &str, steps: usize) -> String {
+        let mut output = Vec::new();
+        {
+            let stepper = Stepper::new(Cursor::new(commands.to_owned()), &mut output)
+                .breakpoint("multiple of 7", |&x: &u32| x % 7 == 0);
+            let mut sim = stepper.attach(Sim::new(0u32, |x| x + 1));
+            sim.step_n(steps);
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_commands() {
+        let output = session("next\n\nc 3\nbogus\nr\nr\n", 20);
+        assert_eq!(
+            headers(&output),
+            vec![
+                "step 1:", "step 2:", "step 3:", "step 6:", "step 7:", "step 14:"
+            ]
+        );
+        assert!(output.contains("unknown command bogus"));
+        assert!(output.contains("breakpoint multiple of 7 hit"));
+    }
+
+    #[test]
+    fn test_dump_and_detach() {
+        let path = std::env::temp_dir().join(format!("aoc-stepper-{}.txt", std::process::id()));
+        let output = session(&format!("n\ndump {}\ndetach\n", path.display()), 10);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "2\n");
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(headers(&output).len(), 2);
+
+        // Running out of input detaches too
+        let output = session("", 10);
+        assert_eq!(headers(&output).len(), 1);
+    }
+
+    #[test]
+    fn test_help() {
+        let output = session(
+            "help
+detach
+",
+            10,
+        );
+        let help = output.split_once("> ").unwrap().1;
+        assert!(help.starts_with(
+            "commands:
+  n, next          take one step
+  c, continue N    take N steps, then pause again
+  r, run           run until a breakpoint is hit
+  d, dump PATH     write the state to PATH
+  detach           stop pausing
+  h, help          show this
+"
+        ));
+    }
+
+    #[test]
+    fn test_not_stepped() {
+        let mut sim = Sim::new(0u32, |x| x + 1).stepped(false);
+        sim.step_n(3);
+        assert_eq!(*sim.state(), 3);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BinaryHeap;
+
+mod hash;
+
+pub use hash::SpatialHash;
+
+/// Squared Euclidean distance, in `i128` so any `i64` coordinates are safe.
+pub fn distance_squared<const D: usize>(a: &[i64; D], b: &[i64; D]) -> i128 {
+    a.iter()
+        .zip(b)
+        .map(|(&a, &b)| (a as i128 - b as i128).pow(2))
+        .sum()
+}
+
+/// A k-d tree over fixed points. Queries return `(index, squared distance)`
+/// pairs indexing into the original points, ordered by distance and then by
+/// index so results don't depend on how the tree happened to split.
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize> {
+    points: Vec<[i64; D]>,
+    // Indices laid out as an implicit balanced tree: the node for a range is
+    // its middle element, splitting on axis `depth % D`
+    order: Vec<usize>,
+}
+
+impl<const D: usize> KdTree<D> {
+    pub fn new(points: Vec<[i64; D]>) -> Self {
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        Self::build(&points, &mut order, 0);
+        Self { points, order }
+    }
+
+    fn build(points: &[[i64; D]], order: &mut [usize], depth: usize) {
+        if order.len() <= 1 {
+            return;
+        }
+        let axis = depth % D;
+        let mid = order.len() / 2;
+        order.select_nth_unstable_by_key(mid, |&i| (points[i][axis], i));
+        let (left, right) = order.split_at_mut(mid);
+        Self::build(points, left, depth + 1);
+        Self::build(points, &mut right[1..], depth + 1);
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn points(&self) -> &[[i64; D]] {
+        &self.points
+    }
+
+    /// The `k` points nearest to `query`, including `query` itself if it is
+    /// one of the points.
+    pub fn nearest(&self, query: &[i64; D], k: usize) -> Vec<(usize, i128)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        self.nearest_in(0, self.order.len(), 0, query, k, &mut heap);
+        let mut out: Vec<(usize, i128)> = heap.into_iter().map(|(d, i)| (i, d)).collect();
+        out.sort_unstable_by_key(|&(i, d)| (d, i));
+        out
+    }
+
+    fn nearest_in(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        query: &[i64; D],
+        k: usize,
+        heap: &mut BinaryHeap<(i128, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+
+        heap.push((distance_squared(point, query), index));
+        if heap.len() > k {
+            heap.pop();
+        }
+
+        let axis = depth % D;
+        let diff = query[axis] as i128 - point[axis] as i128;
+        let (near, far) = if diff < 0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.nearest_in(near.0, near.1, depth + 1, query, k, heap);
+        // Equal distances still need looking at for the index tie break
+        if heap.len() < k || diff * diff <= heap.peek().unwrap().0 {
+            self.nearest_in(far.0, far.1, depth + 1, query, k, heap);
+        }
+    }
+
+    /// Every point within squared distance `radius_squared` of `query`,
+    /// boundary included.
+    pub fn within(&self, query: &[i64; D], radius_squared: i128) -> Vec<(usize, i128)> {
+        let mut out = Vec::new();
+        self.within_in(0, self.order.len(), 0, query, radius_squared, &mut out);
+        out.sort_unstable_by_key(|&(i, d)| (d, i));
+        out
+    }
+
+    fn within_in(
+        &self,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        query: &[i64; D],
+        radius_squared: i128,
+        out: &mut Vec<(usize, i128)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.points[index];
+
+        let d = distance_squared(point, query);
+        if d <= radius_squared {
+            out.push((index, d));
+        }
+
+        let axis = depth % D;
+        let diff = query[axis] as i128 - point[axis] as i128;
+        if diff <= 0 || diff * diff <= radius_squared {
+            self.within_in(lo, mid, depth + 1, query, radius_squared, out);
+        }
+        if diff >= 0 || diff * diff <= radius_squared {
+            self.within_in(mid + 1, hi, depth + 1, query, radius_squared, out);
+        }
+    }
+
+    /// Every pair `(i, j, squared distance)` with `i < j` no further apart
+    /// than `radius_squared`, closest first.
+    pub fn pairs_within(&self, radius_squared: i128) -> Vec<(usize, usize, i128)> {
+        let mut pairs: Vec<(usize, usize, i128)> = (0..self.points.len())
+            .flat_map(|i| {
+                self.within(&self.points[i], radius_squared)
+                    .into_iter()
+                    .filter(move |&(j, _)| j > i)
+                    .map(move |(j, d)| (i, j, d))
+            })
+            .collect();
+        pairs.sort_unstable_by_key(|&(i, j, d)| (d, i, j));
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::aoc::fixtures::scatter;
+
+    fn brute<const D: usize>(points: &[[i64; D]], query: &[i64; D]) -> Vec<(usize, i128)> {
+        let mut all: Vec<(usize, i128)> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, distance_squared(p, query)))
+            .collect();
+        all.sort_unstable_by_key(|&(i, d)| (d, i));
+        all
+    }
+
+    #[test]
+    fn test_nearest() {
+        let points = scatter::<3>(1000, 200);
+        let tree = KdTree::new(points.clone());
+        for query in [[0, 0, 0], [100, 50, 150], points[17]] {
+            let expected = brute(&points, &query);
+            assert_eq!(tree.nearest(&query, 10), expected[..10]);
+            let within: Vec<_> = expected
+                .iter()
+                .copied()
+                .filter(|&(_, d)| d <= 900)
+                .collect();
+            assert_eq!(tree.within(&query, 900), within);
+        }
+    }
+
+    #[test]
+    fn test_ties() {
+        // Four points at the same distance from the origin
+        let tree = KdTree::new(vec![[0, 5], [5, 0], [-5, 0], [0, -5], [9, 9]]);
+        assert_eq!(tree.nearest(&[0, 0], 2), vec![(0, 25), (1, 25)]);
+        assert_eq!(tree.within(&[0, 0], 25).len(), 4);
+    }
+
+    #[test]
+    fn test_pairs_within() {
+        let points = scatter::<3>(300, 100);
+        let tree = KdTree::new(points.clone());
+        let mut expected = Vec::new();
+        for i in 0..points.len() {
+            for j in i + 1..points.len() {
+                let d = distance_squared(&points[i], &points[j]);
+                if d <= 150 {
+                    expected.push((i, j, d));
+                }
+            }
+        }
+        expected.sort_unstable_by_key(|&(i, j, d)| (d, i, j));
+        assert_eq!(tree.pairs_within(150), expected);
+    }
+}
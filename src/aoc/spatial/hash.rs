@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use super::distance_squared;
+
+/// Points bucketed into cubes one radius wide, so everything within the
+/// radius of a point is in the `3^D` cubes around it. Faster than a
+/// `KdTree` when the radius is fixed up front and small next to the spread
+/// of the points.
+#[derive(Debug, Clone)]
+pub struct SpatialHash<const D: usize> {
+    points: Vec<[i64; D]>,
+    radius: i64,
+    cells: HashMap<[i64; D], Vec<usize>>,
+}
+
+impl<const D: usize> SpatialHash<D> {
+    pub fn new(points: Vec<[i64; D]>, radius: i64) -> Self {
+        assert!(radius > 0, "Radius must be positive");
+        let mut cells: HashMap<[i64; D], Vec<usize>> = HashMap::new();
+        for (i, p) in points.iter().enumerate() {
+            cells.entry(Self::cell(p, radius)).or_default().push(i);
+        }
+        Self {
+            points,
+            radius,
+            cells,
+        }
+    }
+
+    fn cell(p: &[i64; D], radius: i64) -> [i64; D] {
+        p.map(|c| c.div_euclid(radius))
+    }
+
+    pub fn points(&self) -> &[[i64; D]] {
+        &self.points
+    }
+
+    pub fn radius(&self) -> i64 {
+        self.radius
+    }
+
+    fn candidates(&self, query: &[i64; D]) -> impl Iterator<Item = usize> + '_ {
+        let centre = Self::cell(query, self.radius);
+        (0..3usize.pow(D as u32)).flat_map(move |mut offsets| {
+            let mut cell = centre;
+            for c in &mut cell {
+                *c += (offsets % 3) as i64 - 1;
+                offsets /= 3;
+            }
+            self.cells.get(&cell).into_iter().flatten().copied()
+        })
+    }
+
+    /// Every point within the radius of `query`, boundary included, as
+    /// `(index, squared distance)` ordered by distance then index.
+    pub fn within(&self, query: &[i64; D]) -> Vec<(usize, i128)> {
+        let radius_squared = (self.radius as i128).pow(2);
+        let mut out: Vec<(usize, i128)> = self
+            .candidates(query)
+            .map(|i| (i, distance_squared(&self.points[i], query)))
+            .filter(|&(_, d)| d <= radius_squared)
+            .collect();
+        out.sort_unstable_by_key(|&(i, d)| (d, i));
+        out
+    }
+
+    /// Every pair `(i, j, squared distance)` with `i < j` within the radius,
+    /// closest first.
+    pub fn pairs_within(&self) -> Vec<(usize, usize, i128)> {
+        let mut pairs: Vec<(usize, usize, i128)> = (0..self.points.len())
+            .flat_map(|i| {
+                self.within(&self.points[i])
+                    .into_iter()
+                    .filter(move |&(j, _)| j > i)
+                    .map(move |(j, d)| (i, j, d))
+            })
+            .collect();
+        pairs.sort_unstable_by_key(|&(i, j, d)| (d, i, j));
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{super::KdTree, *};
+    use crate::aoc::fixtures::scatter;
+
+    #[test]
+    fn test_matches_kd_tree() {
+        let mut points = scatter::<3>(500, 300);
+        points.push([-7, -7, -7]);
+        let tree = KdTree::new(points.clone());
+        let hash = SpatialHash::new(points.clone(), 25);
+        assert_eq!(hash.pairs_within(), tree.pairs_within(625));
+        for query in [[0, 0, 0], [-20, -5, 3], points[42]] {
+            assert_eq!(hash.within(&query), tree.within(&query, 625));
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use super::{
+    grid::Point,
+    interval::{Bound, Interval},
+};
+
+/// Axis-aligned rectangle made of two half-open intervals, so
+/// `Rect::new(0..2, 0..3)` covers six unit cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: Interval<i64>,
+    pub y: Interval<i64>,
+}
+
+impl Rect {
+    pub fn new(x: impl Into<Interval<i64>>, y: impl Into<Interval<i64>>) -> Self {
+        Self {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// The rectangle of grid cells with `a` and `b` as opposite corners,
+    /// both included.
+    pub fn from_corners(a: Point, b: Point) -> Self {
+        let (x0, x1) = (a.x.min(b.x) as i64, a.x.max(b.x) as i64);
+        let (y0, y1) = (a.y.min(b.y) as i64, a.y.max(b.y) as i64);
+        Self::new(x0..=x1, y0..=y1)
+    }
+
+    pub fn width(&self) -> i64 {
+        self.x.len()
+    }
+
+    pub fn height(&self) -> i64 {
+        self.y.len()
+    }
+
+    pub fn area(&self) -> i64 {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty() || self.y.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.x.contains(point.x as i64) && self.y.contains(point.y as i64)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        Some(Self {
+            x: self.x.intersection(&other.x)?,
+            y: self.y.intersection(&other.y)?,
+        })
+    }
+
+    /// Every grid point inside the rectangle, skipping negative coordinates.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        let xs = self.x.start().max(0)..self.x.end().max(0);
+        let ys = self.y.start().max(0)..self.y.end().max(0);
+        ys.flat_map(move |y| xs.clone().map(move |x| Point::new(x as usize, y as usize)))
+    }
+}
+
+/// Maximal pieces of the line paired with how many intervals cover them, in
+/// order. Pieces nothing covers are left out.
+pub fn coverage<T: Bound>(intervals: &[Interval<T>]) -> Vec<(Interval<T>, usize)> {
+    let mut events: Vec<(T, isize)> = intervals
+        .iter()
+        .filter(|i| !i.is_empty())
+        .flat_map(|i| [(i.start(), 1), (i.end(), -1)])
+        .collect();
+    // Ends sort before starts at the same point since intervals are half-open
+    events.sort();
+
+    let mut out: Vec<(Interval<T>, usize)> = Vec::new();
+    let mut depth = 0isize;
+    let mut prev = None;
+    for (x, delta) in events {
+        if let Some(start) = prev
+            && depth > 0
+            && start < x
+        {
+            match out.last_mut() {
+                Some((last, d)) if last.end() == start && *d == depth as usize => {
+                    *last = Interval::new(last.start(), x);
+                }
+                _ => out.push((Interval::new(start, x), depth as usize)),
+            }
+        }
+        depth += delta;
+        prev = Some(x);
+    }
+    out
+}
+
+/// Greatest number of intervals covering any single point.
+pub fn max_overlap<T: Bound>(intervals: &[Interval<T>]) -> usize {
+    coverage(intervals)
+        .into_iter()
+        .map(|(_, depth)| depth)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Area covered by at least one rectangle.
+pub fn union_area(rects: &[Rect]) -> i64 {
+    area_covered_at_least(rects, 1)
+}
+
+/// Area covered by at least `k` of the rectangles, by sweeping across x and
+/// keeping a count per compressed y slab.
+pub fn area_covered_at_least(rects: &[Rect], k: usize) -> i64 {
+    let rects: Vec<&Rect> = rects.iter().filter(|r| !r.is_empty()).collect();
+    if k == 0 || rects.is_empty() {
+        return 0;
+    }
+
+    let mut ys: Vec<i64> = rects
+        .iter()
+        .flat_map(|r| [r.y.start(), r.y.end()])
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+    let slab = |y: i64| ys.binary_search(&y).unwrap();
+
+    let mut events: Vec<(i64, isize, usize, usize)> = rects
+        .iter()
+        .flat_map(|r| {
+            let (lo, hi) = (slab(r.y.start()), slab(r.y.end()));
+            [(r.x.start(), 1, lo, hi), (r.x.end(), -1, lo, hi)]
+        })
+        .collect();
+    events.sort_unstable();
+
+    let mut counts = vec![0usize; ys.len() - 1];
+    let mut area = 0;
+    let mut prev_x = events[0].0;
+    for (x, delta, lo, hi) in events {
+        if x > prev_x {
+            let covered: i64 = counts
+                .iter()
+                .enumerate()
+                .filter(|&(_, &c)| c >= k)
+                .map(|(i, _)| ys[i + 1] - ys[i])
+                .sum();
+            area += covered * (x - prev_x);
+            prev_x = x;
+        }
+        for count in &mut counts[lo..hi] {
+            *count = count.checked_add_signed(delta).unwrap();
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rect() {
+        let r = Rect::from_corners(Point::new(5, 1), Point::new(2, 3));
+        assert_eq!(r, Rect::new(2..6, 1..4));
+        assert_eq!(r.area(), 12);
+        assert!(r.contains(&Point::new(5, 3)));
+        assert!(!r.contains(&Point::new(6, 3)));
+        assert_eq!(r.points().count(), 12);
+        assert_eq!(
+            r.intersection(&Rect::new(4..10, 0..2)),
+            Some(Rect::new(4..6, 1..2))
+        );
+    }
+
+    #[test]
+    fn test_coverage() {
+        let intervals = [
+            Interval::new(0, 10),
+            Interval::new(5, 15),
+            Interval::new(10, 20),
+            Interval::new(12, 13),
+        ];
+        assert_eq!(
+            coverage(&intervals),
+            vec![
+                (Interval::new(0, 5), 1),
+                (Interval::new(5, 12), 2),
+                (Interval::new(12, 13), 3),
+                (Interval::new(13, 15), 2),
+                (Interval::new(15, 20), 1),
+            ]
+        );
+        assert_eq!(max_overlap(&intervals), 3);
+        // Touching half-open intervals don't overlap
+        assert_eq!(max_overlap(&[Interval::new(0, 5), Interval::new(5, 9)]), 1);
+        assert_eq!(max_overlap::<i32>(&[]), 0);
+    }
+
+    #[test]
+    fn test_union_area() {
+        // Fabric claims: two overlapping 4x4 squares and a separate 2x2
+        let claims = [
+            Rect::new(1..5, 3..7),
+            Rect::new(3..7, 1..5),
+            Rect::new(5..7, 5..7),
+        ];
+        assert_eq!(union_area(&claims), 32);
+        assert_eq!(area_covered_at_least(&claims, 2), 4);
+        assert_eq!(area_covered_at_least(&claims, 3), 0);
+
+        let nested = [Rect::new(0..10, 0..10), Rect::new(2..4, 2..4)];
+        assert_eq!(union_area(&nested), 100);
+        assert_eq!(area_covered_at_least(&nested, 2), 4);
+
+        let huge = [Rect::new(0..1_000_000_000, 0..1_000_000_000)];
+        assert_eq!(union_area(&huge), 1_000_000_000_000_000_000);
+    }
+}
@@ -1,4 +1,4 @@
-use aoc2025::aoc;
+use aoc2025::aoc::{self, sim::Sim};
 
 const START: i64 = 50;
 const DIAL_SIZE: i64 = 100;
@@ -18,17 +18,45 @@ mod part1 {
         sign * num.parse::<i64>().unwrap()
     }
 
-    pub fn calculate(input: &str) -> usize {
-        let mut pos = START;
-        let mut zeroes = 0;
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Dial {
+        pub next: usize,
+        pub pos: i64,
+        pub zeroes: usize,
+    }
 
-        for i in input.lines().map(parse_line) {
-            pos += i;
-            pos = pos.rem_euclid(DIAL_SIZE);
-            zeroes += (pos == 0) as usize;
+    impl std::fmt::Display for Dial {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(
+                f,
+                "dial at {} after {} rotations, {} zeroes",
+                self.pos, self.next, self.zeroes
+            )
         }
+    }
 
-        zeroes
+    pub fn calculate(input: &str, step: bool) -> usize {
+        let rotations: Vec<i64> = input.lines().map(parse_line).collect();
+
+        let mut sim = Sim::new(
+            Dial {
+                next: 0,
+                pos: START,
+                zeroes: 0,
+            },
+            |dial| {
+                let pos = (dial.pos + rotations[dial.next]).rem_euclid(DIAL_SIZE);
+                Dial {
+                    next: dial.next + 1,
+                    pos,
+                    zeroes: dial.zeroes + (pos == 0) as usize,
+                }
+            },
+        )
+        .stepped(step);
+        sim.run_until(|dial| dial.next == rotations.len());
+
+        sim.state().zeroes
     }
 
     #[cfg(test)]
@@ -38,7 +66,7 @@ mod part1 {
         #[test]
         fn test_example() {
             let input = aoc::example::example_string("day1.txt");
-            assert_eq!(calculate(&input), 3);
+            assert_eq!(calculate(&input, false), 3);
         }
     }
 }
@@ -117,6 +145,6 @@ fn main() {
 
     let input = cli.input_string();
 
-    println!("Part 1: {}", part1::calculate(&input));
+    println!("Part 1: {}", part1::calculate(&input, cli.step));
     println!("Part 2: {}", part2::calculate(&input)); // 5978
 }
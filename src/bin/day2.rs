@@ -1,23 +1,18 @@
-use std::ops::RangeInclusive;
-
-use aoc2025::aoc;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Range(usize, usize);
-
-impl Range {
-    pub fn iter(&self) -> RangeInclusive<usize> {
-        self.0..=self.1
-    }
-}
-
-fn parse_pair(s: &str) -> Range {
-    let (l, r) = s.split_once('-').unwrap();
-    Range(l.parse().unwrap(), r.parse().unwrap())
+use aoc2025::aoc::{
+    self,
+    interval::{Interval, RangeSet},
+};
+
+/// Every ID in the input's ranges, each once even where ranges overlap.
+pub fn parse_input(input: &str) -> RangeSet<usize> {
+    input
+        .split(',')
+        .map(|s| s.trim().parse::<Interval<usize>>().unwrap())
+        .collect()
 }
 
-pub fn parse_input(input: &str) -> Vec<Range> {
-    input.split(',').map(parse_pair).collect()
+pub fn ids(ranges: &RangeSet<usize>) -> impl Iterator<Item = usize> + '_ {
+    ranges.iter().flat_map(Interval::range)
 }
 
 mod part1 {
@@ -42,11 +37,7 @@ mod part1 {
     }
 
     pub fn calculate(input: &str) -> usize {
-        parse_input(input)
-            .iter()
-            .flat_map(Range::iter)
-            .filter(|i| is_invalid(*i))
-            .sum()
+        ids(&parse_input(input)).filter(|i| is_invalid(*i)).sum()
     }
 
     #[cfg(test)]
@@ -117,11 +108,7 @@ mod part2 {
     }
 
     pub fn calculate(input: &str) -> usize {
-        parse_input(input)
-            .iter()
-            .flat_map(Range::iter)
-            .filter(|i| is_invalid(*i))
-            .sum()
+        ids(&parse_input(input)).filter(|i| is_invalid(*i)).sum()
     }
 
     #[cfg(test)]
@@ -1,7 +1,8 @@
 use std::fmt::Write;
 
-use aoc2025::aoc::{self, grid::Grid};
+use aoc2025::aoc::{self, grid::Grid, sim::Sim};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Empty,
     Paper,
@@ -36,24 +37,31 @@ impl std::str::FromStr for State {
 
 pub type Floor = Grid<Vec<State>>;
 
+/// How many rolls of paper surround each cell.
+pub fn paper_neighbours(floor: &Floor) -> Grid<Vec<u8>> {
+    let counts = Grid::<Vec<u8>>::fill(floor.width(), floor.height(), 0);
+
+    for cell in floor {
+        let point = cell.point();
+        match *cell.value() {
+            State::Empty => {}
+            State::Paper => {
+                for mut neighbour in counts.cell_at_point(&point).unwrap().neighbours() {
+                    *neighbour.value_mut() += 1;
+                }
+            }
+        }
+    }
+
+    counts
+}
+
 mod part1 {
     use super::*;
 
     pub fn calculate(input: &str) -> usize {
         let floor: Floor = input.parse().unwrap();
-        let counts = Grid::<Vec<u8>>::fill(floor.width(), floor.height(), 0);
-
-        for cell in &floor {
-            let point = cell.point();
-            match *cell.value() {
-                State::Empty => {}
-                State::Paper => {
-                    for mut neighbour in counts.cell_at_point(&point).unwrap().neighbours() {
-                        *neighbour.value_mut() += 1;
-                    }
-                }
-            }
-        }
+        let counts = paper_neighbours(&floor);
 
         let mut total = 0;
 
@@ -82,12 +90,46 @@ mod part1 {
         }
     }
 }
-/*
 mod part2 {
     use super::*;
 
-    pub fn calculate(input: &str) -> usize {
-        0
+    #[derive(Clone, PartialEq, Eq)]
+    pub struct Clearing {
+        pub floor: Floor,
+        pub removed: usize,
+    }
+
+    impl std::fmt::Display for Clearing {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}\n{} rolls removed", self.floor, self.removed)
+        }
+    }
+
+    // Removes every roll a forklift can reach at once
+    fn clear(clearing: &Clearing) -> Clearing {
+        let counts = paper_neighbours(&clearing.floor);
+        let floor = clearing.floor.clone();
+        let mut removed = clearing.removed;
+
+        for cell in &clearing.floor {
+            if *cell.value() == State::Paper
+                && *counts.cell_at_point(&cell.point()).unwrap().value() < 4
+            {
+                *floor.value_at_mut(cell.x, cell.y).unwrap() = State::Empty;
+                removed += 1;
+            }
+        }
+
+        Clearing { floor, removed }
+    }
+
+    pub fn calculate(input: &str, step: bool) -> usize {
+        let floor: Floor = input.parse().unwrap();
+
+        let mut sim = Sim::new(Clearing { floor, removed: 0 }, clear).stepped(step);
+        sim.run_until_stable();
+
+        sim.state().removed
     }
 
     #[cfg(test)]
@@ -97,16 +139,16 @@ mod part2 {
         #[test]
         fn test_example() {
             let input = aoc::example::example_string("day4.txt");
-            assert_eq!(calculate(&input), 0);
+            assert_eq!(calculate(&input, false), 43);
         }
     }
 }
-*/
+
 fn main() {
     let cli = aoc::cli::parse();
 
     let input = cli.input_string();
 
     println!("Part 1: {}", part1::calculate(&input));
-    // println!("Part 2: {}", part2::calculate(&input));
+    println!("Part 2: {}", part2::calculate(&input, cli.step));
 }
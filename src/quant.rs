@@ -1,17 +1,33 @@
 use std::ops::{Div, Mul, Rem};
 
+pub mod bigint;
+pub mod ilp;
+pub mod matrix;
+pub mod poly;
+pub mod rational;
+pub mod recurrence;
+
+pub use bigint::{BigInt, BigUint};
+pub use matrix::{Matrix, SMatrix};
+pub use poly::Polynomial;
+pub use rational::Rational;
+
 pub fn lcm<T>(a: T, b: T) -> T
 where
-    T: Mul<Output = T> + Div<Output = T> + Eq + NumConsts + Rem<Output = T> + Copy,
+    T: Mul<Output = T> + Div<Output = T> + Eq + NumConsts + Rem<Output = T> + Clone,
 {
-    a * b / gcd(a, b)
+    a.clone() * b.clone() / gcd(a, b)
 }
 
 pub fn gcd<T>(a: T, b: T) -> T
 where
-    T: Rem<Output = T> + Eq + NumConsts + Copy,
+    T: Rem<Output = T> + Eq + NumConsts + Clone,
 {
-    if b == T::ZERO { a } else { gcd(b, a % b) }
+    if b == T::ZERO {
+        a
+    } else {
+        gcd(b.clone(), a % b)
+    }
 }
 
 pub trait QuantIter: Iterator {
@@ -22,7 +38,7 @@ pub trait QuantIter: Iterator {
             + Eq
             + NumConsts
             + Rem<Output = Self::Item>
-            + Copy,
+            + Clone,
         Self: Sized,
     {
         self.next().map(|first| self.fold(first, lcm))
@@ -30,7 +46,7 @@ pub trait QuantIter: Iterator {
 
     fn gcd(mut self) -> Option<Self::Item>
     where
-        Self::Item: Rem<Output = Self::Item> + Eq + NumConsts + Copy,
+        Self::Item: Rem<Output = Self::Item> + Eq + NumConsts + Clone,
         Self: Sized,
     {
         self.next().map(|first| self.fold(first, gcd))
@@ -100,6 +116,32 @@ impl_rem_euclid!(i64);
 impl_rem_euclid!(i128);
 impl_rem_euclid!(isize);
 
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+macro_rules! impl_checked_add {
+    ($typ:ty) => {
+        impl CheckedAdd for $typ {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$typ>::checked_add(self, other)
+            }
+        }
+    };
+}
+
+impl_checked_add!(u8);
+impl_checked_add!(u16);
+impl_checked_add!(u32);
+impl_checked_add!(u64);
+impl_checked_add!(u128);
+impl_checked_add!(usize);
+impl_checked_add!(i8);
+impl_checked_add!(i16);
+impl_checked_add!(i32);
+impl_checked_add!(i64);
+impl_checked_add!(i128);
+impl_checked_add!(isize);
+
 pub trait ILog10 {
     fn ilog10(self) -> u32;
 }
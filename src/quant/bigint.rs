@@ -0,0 +1,891 @@
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::{NumConsts, RemEuclid, UnsignedAbs};
+
+// Below this many limbs schoolbook multiplication beats Karatsuba
+const KARATSUBA_THRESHOLD: usize = 32;
+
+// Largest power of ten that fits in a limb, used for decimal conversion
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl Display for ParseBigIntError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid digit found in string")
+    }
+}
+
+impl std::error::Error for ParseBigIntError {}
+
+/// Little-endian base 2^32 limbs with no trailing zero limbs, so zero is empty.
+/// Stored as a `Cow` so that `NumConsts` can be provided as real constants.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Cow<'static, [u32]>,
+}
+
+impl BigUint {
+    fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        Self {
+            limbs: Cow::Owned(limbs),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => 32 * self.limbs.len() as u64 - top.leading_zeros() as u64,
+        }
+    }
+
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut acc = Self::ONE;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = &acc * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        acc
+    }
+
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = divmod_limbs(&self.limbs, &other.limbs);
+        (Self::from_limbs(q), Self::from_limbs(r))
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        (self.limbs.len() <= 4).then(|| {
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &limb| (acc << 32) | limb as u128)
+        })
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.to_u128().and_then(|x| x.try_into().ok())
+    }
+}
+
+impl NumConsts for BigUint {
+    const ZERO: Self = BigUint {
+        limbs: Cow::Borrowed(&[]),
+    };
+    const ONE: Self = BigUint {
+        limbs: Cow::Borrowed(&[1]),
+    };
+}
+
+impl Default for BigUint {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+macro_rules! impl_biguint_from {
+    ($typ:ty) => {
+        impl From<$typ> for BigUint {
+            fn from(x: $typ) -> Self {
+                let mut x = x as u128;
+                let mut limbs = Vec::new();
+                while x > 0 {
+                    limbs.push(x as u32);
+                    x >>= 32;
+                }
+                Self::from_limbs(limbs)
+            }
+        }
+    };
+}
+impl_biguint_from!(u8);
+impl_biguint_from!(u16);
+impl_biguint_from!(u32);
+impl_biguint_from!(u64);
+impl_biguint_from!(u128);
+impl_biguint_from!(usize);
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('+').unwrap_or(s);
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        let mut limbs = Vec::new();
+        let head = s.len() % DECIMAL_DIGITS;
+        let chunks = std::iter::once(&s[..head])
+            .filter(|chunk| !chunk.is_empty())
+            .chain(s.as_bytes()[head..].chunks(DECIMAL_DIGITS).map(|chunk| {
+                // Already checked to be ascii digits
+                std::str::from_utf8(chunk).unwrap()
+            }));
+
+        for chunk in chunks {
+            let scale = 10u32.pow(chunk.len() as u32);
+            mul_add_small(&mut limbs, scale, chunk.parse().unwrap());
+        }
+
+        Ok(Self::from_limbs(limbs))
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut chunks = Vec::new();
+        let mut limbs = self.limbs.to_vec();
+        while !limbs.is_empty() {
+            chunks.push(div_small(&mut limbs, DECIMAL_BASE));
+        }
+
+        let mut s = String::new();
+        match chunks.split_last() {
+            None => s.push('0'),
+            Some((top, rest)) => {
+                s.push_str(&top.to_string());
+                for chunk in rest.iter().rev() {
+                    s.push_str(&format!("{chunk:0width$}", width = DECIMAL_DIGITS));
+                }
+            }
+        }
+
+        f.pad_integral(true, "", &s)
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: &BigUint) -> Self::Output {
+        BigUint::from_limbs(add_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: &BigUint) -> Self::Output {
+        if cmp_limbs(&self.limbs, &rhs.limbs) == Ordering::Less {
+            panic!("attempt to subtract with overflow");
+        }
+        BigUint::from_limbs(sub_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> Self::Output {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &rhs.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, rhs: &BigUint) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, rhs: &BigUint) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl UnsignedAbs for BigUint {
+    type Output = BigUint;
+    fn unsigned_abs(self) -> Self::Output {
+        self
+    }
+}
+
+impl RemEuclid for BigUint {
+    fn rem_euclid(self, other: Self) -> Self {
+        self % other
+    }
+}
+
+/// Sign-magnitude integer; zero is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn signum(&self) -> i32 {
+        if self.negative {
+            -1
+        } else if self.is_zero() {
+            0
+        } else {
+            1
+        }
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::from_parts(false, self.magnitude.clone())
+    }
+
+    pub fn pow(&self, exp: u32) -> Self {
+        Self::from_parts(self.negative && exp % 2 == 1, self.magnitude.pow(exp))
+    }
+
+    /// Truncating division, matching the primitive integer types.
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = self.magnitude.div_rem(&other.magnitude);
+        (
+            Self::from_parts(self.negative != other.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            magnitude.try_into().ok()
+        }
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        self.to_i128().and_then(|x| x.try_into().ok())
+    }
+}
+
+impl NumConsts for BigInt {
+    const ZERO: Self = BigInt {
+        negative: false,
+        magnitude: BigUint::ZERO,
+    };
+    const ONE: Self = BigInt {
+        negative: false,
+        magnitude: BigUint::ONE,
+    };
+}
+
+impl Default for BigInt {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::from_parts(false, magnitude)
+    }
+}
+
+macro_rules! impl_bigint_from {
+    ($typ:ty) => {
+        impl From<$typ> for BigInt {
+            fn from(x: $typ) -> Self {
+                Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+            }
+        }
+    };
+    ($typ:ty, u) => {
+        impl From<$typ> for BigInt {
+            fn from(x: $typ) -> Self {
+                Self::from_parts(false, BigUint::from(x))
+            }
+        }
+    };
+}
+impl_bigint_from!(i8);
+impl_bigint_from!(i16);
+impl_bigint_from!(i32);
+impl_bigint_from!(i64);
+impl_bigint_from!(i128);
+impl_bigint_from!(isize);
+impl_bigint_from!(u8, u);
+impl_bigint_from!(u16, u);
+impl_bigint_from!(u32, u);
+impl_bigint_from!(u64, u);
+impl_bigint_from!(u128, u);
+impl_bigint_from!(usize, u);
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(rest) if !rest.starts_with('+') => Ok(Self::from_parts(true, rest.parse()?)),
+            Some(_) => Err(ParseBigIntError),
+            None => Ok(Self::from_parts(false, s.parse()?)),
+        }
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_string())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        Self::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &rhs.magnitude);
+        }
+        match self.magnitude.cmp(&rhs.magnitude) {
+            Ordering::Equal => BigInt::ZERO,
+            Ordering::Greater => {
+                BigInt::from_parts(self.negative, &self.magnitude - &rhs.magnitude)
+            }
+            Ordering::Less => BigInt::from_parts(rhs.negative, &rhs.magnitude - &self.magnitude),
+        }
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> Self::Output {
+        BigInt::from_parts(
+            self.negative != rhs.negative,
+            &self.magnitude * &rhs.magnitude,
+        )
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, rhs: &BigInt) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, rhs: &BigInt) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl UnsignedAbs for BigInt {
+    type Output = BigUint;
+    fn unsigned_abs(self) -> Self::Output {
+        self.magnitude
+    }
+}
+
+impl RemEuclid for BigInt {
+    fn rem_euclid(self, other: Self) -> Self {
+        let r = &self % &other;
+        if r.negative {
+            BigInt::from_parts(false, &other.magnitude - &r.magnitude)
+        } else {
+            r
+        }
+    }
+}
+
+// Derive the owned and assigning operator variants from the `&a op &b` impls
+macro_rules! forward_binop {
+    ($typ:ty, $trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait<$typ> for $typ {
+            type Output = $typ;
+
+            fn $method(self, rhs: $typ) -> Self::Output {
+                (&self).$method(&rhs)
+            }
+        }
+
+        impl $trait<&$typ> for $typ {
+            type Output = $typ;
+
+            fn $method(self, rhs: &$typ) -> Self::Output {
+                (&self).$method(rhs)
+            }
+        }
+
+        impl $trait<$typ> for &$typ {
+            type Output = $typ;
+
+            fn $method(self, rhs: $typ) -> Self::Output {
+                self.$method(&rhs)
+            }
+        }
+
+        impl $assign_trait<$typ> for $typ {
+            fn $assign_method(&mut self, rhs: $typ) {
+                *self = (&*self).$method(&rhs);
+            }
+        }
+
+        impl $assign_trait<&$typ> for $typ {
+            fn $assign_method(&mut self, rhs: &$typ) {
+                *self = (&*self).$method(rhs);
+            }
+        }
+    };
+}
+forward_binop!(BigUint, Add, add, AddAssign, add_assign);
+forward_binop!(BigUint, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigUint, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigUint, Div, div, DivAssign, div_assign);
+forward_binop!(BigUint, Rem, rem, RemAssign, rem_assign);
+forward_binop!(BigInt, Add, add, AddAssign, add_assign);
+forward_binop!(BigInt, Sub, sub, SubAssign, sub_assign);
+forward_binop!(BigInt, Mul, mul, MulAssign, mul_assign);
+forward_binop!(BigInt, Div, div, DivAssign, div_assign);
+forward_binop!(BigInt, Rem, rem, RemAssign, rem_assign);
+
+impl std::iter::Sum for BigUint {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl std::iter::Product for BigUint {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl std::iter::Sum for BigInt {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl std::iter::Product for BigInt {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &x) in long.iter().enumerate() {
+        let sum = x as u64 + short.get(i).copied().unwrap_or(0) as u64 + carry;
+        out.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        out.push(carry as u32);
+    }
+    out
+}
+
+// Requires a >= b
+fn sub_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &x) in a.iter().enumerate() {
+        let diff = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        out.push(diff as u32);
+        borrow = (diff < 0) as i64;
+    }
+    debug_assert_eq!(borrow, 0);
+    trim(&mut out);
+    out
+}
+
+// acc += x << (32 * shift)
+fn add_shifted(acc: &mut Vec<u32>, x: &[u32], shift: usize) {
+    if acc.len() < x.len() + shift {
+        acc.resize(x.len() + shift, 0);
+    }
+    let mut carry = 0u64;
+    let mut i = shift;
+    for &limb in x {
+        let sum = acc[i] as u64 + limb as u64 + carry;
+        acc[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+    while carry > 0 {
+        if i == acc.len() {
+            acc.push(0);
+        }
+        let sum = acc[i] as u64 + carry;
+        acc[i] = sum as u32;
+        carry = sum >> 32;
+        i += 1;
+    }
+}
+
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut out = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let prod = x as u64 * y as u64 + out[i + j] as u64 + carry;
+            out[i + j] = prod as u32;
+            carry = prod >> 32;
+        }
+        out[i + b.len()] = carry as u32;
+    }
+    trim(&mut out);
+    out
+}
+
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+
+    // a = a1 * B^m + a0, b = b1 * B^m + b0
+    let m = a.len().max(b.len()) / 2;
+    let split = |x: &[u32]| {
+        let (lo, hi) = x.split_at(m.min(x.len()));
+        let mut lo = lo.to_vec();
+        trim(&mut lo);
+        (lo, hi.to_vec())
+    };
+    let (a0, a1) = split(a);
+    let (b0, b1) = split(b);
+
+    let z0 = mul_limbs(&a0, &b0);
+    let z2 = mul_limbs(&a1, &b1);
+    let z1 = mul_limbs(&add_limbs(&a0, &a1), &add_limbs(&b0, &b1));
+    let z1 = sub_limbs(&sub_limbs(&z1, &z0), &z2);
+
+    let mut out = z0;
+    add_shifted(&mut out, &z1, m);
+    add_shifted(&mut out, &z2, 2 * m);
+    trim(&mut out);
+    out
+}
+
+// limbs = limbs * mul + add
+fn mul_add_small(limbs: &mut Vec<u32>, mul: u32, add: u32) {
+    let mut carry = add as u64;
+    for limb in limbs.iter_mut() {
+        let prod = *limb as u64 * mul as u64 + carry;
+        *limb = prod as u32;
+        carry = prod >> 32;
+    }
+    if carry > 0 {
+        limbs.push(carry as u32);
+    }
+}
+
+// Divides in place, returning the remainder
+fn div_small(limbs: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut rem = 0u64;
+    for limb in limbs.iter_mut().rev() {
+        let cur = (rem << 32) | *limb as u64;
+        *limb = (cur / divisor as u64) as u32;
+        rem = cur % divisor as u64;
+    }
+    trim(limbs);
+    rem as u32
+}
+
+fn shl_bits(x: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return x.to_vec();
+    }
+    let mut out = Vec::with_capacity(x.len() + 1);
+    let mut carry = 0u32;
+    for &limb in x {
+        out.push((limb << shift) | carry);
+        carry = limb >> (32 - shift);
+    }
+    out.push(carry);
+    out
+}
+
+fn shr_bits(x: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return x.to_vec();
+    }
+    let mut out = vec![0u32; x.len()];
+    for i in 0..x.len() {
+        let hi = x.get(i + 1).map_or(0, |&next| next << (32 - shift));
+        out[i] = (x[i] >> shift) | hi;
+    }
+    trim(&mut out);
+    out
+}
+
+// Knuth's algorithm D (TAOCP 4.3.1)
+fn divmod_limbs(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if v.is_empty() {
+        panic!("attempt to divide by zero");
+    }
+    if cmp_limbs(u, v) == Ordering::Less {
+        return (Vec::new(), u.to_vec());
+    }
+    if v.len() == 1 {
+        let mut q = u.to_vec();
+        let r = div_small(&mut q, v[0]);
+        return (q, if r == 0 { Vec::new() } else { vec![r] });
+    }
+
+    const B: u64 = 1 << 32;
+
+    // Normalise so the top limb of the divisor has its high bit set
+    let shift = v.last().unwrap().leading_zeros();
+    let vn = shl_bits(v, shift);
+    let vn = &vn[..v.len()];
+    let mut un = shl_bits(u, shift);
+    if un.len() == u.len() {
+        un.push(0);
+    }
+
+    let n = vn.len();
+    let m = u.len() - n;
+    let mut q = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        let num = ((un[j + n] as u64) << 32) | un[j + n - 1] as u64;
+        let mut qhat = num / vn[n - 1] as u64;
+        let mut rhat = num % vn[n - 1] as u64;
+
+        while qhat >= B || qhat * vn[n - 2] as u64 > ((rhat << 32) | un[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += vn[n - 1] as u64;
+            if rhat >= B {
+                break;
+            }
+        }
+
+        // un[j..=j+n] -= qhat * vn
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let prod = qhat * vn[i] as u64;
+            let t = un[i + j] as i64 - borrow - (prod & 0xFFFF_FFFF) as i64;
+            un[i + j] = t as u32;
+            borrow = (prod >> 32) as i64 - (t >> 32);
+        }
+        let t = un[j + n] as i64 - borrow;
+        un[j + n] = t as u32;
+
+        q[j] = qhat as u32;
+        if t < 0 {
+            // Overshot by one, add the divisor back
+            q[j] = q[j].wrapping_sub(1);
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = un[i + j] as u64 + vn[i] as u64 + carry;
+                un[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u32);
+        }
+    }
+
+    trim(&mut q);
+    (q, shr_bits(&un[..n], shift))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::quant::{QuantIter, gcd, lcm};
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_display() {
+        assert_eq!(BigUint::ZERO.to_string(), "0");
+        assert_eq!(big("000").to_string(), "0");
+        assert_eq!(
+            big("123456789012345678901234567890").to_string(),
+            "123456789012345678901234567890"
+        );
+        assert_eq!(big("1000000000").to_string(), "1000000000");
+        assert_eq!(format!("{:>5}", BigUint::from(42u8)), "   42");
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("".parse::<BigUint>().is_err());
+
+        assert_eq!("-42".parse::<BigInt>().unwrap(), BigInt::from(-42));
+        assert_eq!("-0".parse::<BigInt>().unwrap(), BigInt::ZERO);
+        assert_eq!(
+            BigInt::from(-1234567890123i64).to_string(),
+            "-1234567890123"
+        );
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigUint::from(u128::MAX);
+        let b = &a + &BigUint::ONE;
+        assert_eq!(b.to_string(), "340282366920938463463374607431768211456");
+        assert_eq!(&b - &BigUint::ONE, a);
+        assert_eq!(b.bits(), 129);
+
+        let prod = &a * &a;
+        assert_eq!(
+            prod.to_string(),
+            "115792089237316195423570985008687907852589419931798687112530834793049593217025"
+        );
+        assert_eq!(&prod / &a, a);
+        assert_eq!(&prod % &a, BigUint::ZERO);
+
+        let (q, r) = big("1000000000000000000000000000007").div_rem(&big("1000000000000"));
+        assert_eq!(q, big("1000000000000000000"));
+        assert_eq!(r, big("7"));
+
+        assert_eq!(
+            BigUint::from(2u8).pow(100).to_string(),
+            "1267650600228229401496703205376"
+        );
+    }
+
+    #[test]
+    fn test_karatsuba_matches_schoolbook() {
+        let a: Vec<u32> = (0..150u32).map(|i| i.wrapping_mul(2654435761)).collect();
+        let b: Vec<u32> = (0..97u32)
+            .map(|i| i.wrapping_mul(40503) ^ 0xdeadbeef)
+            .collect();
+        assert_eq!(mul_limbs(&a, &b), mul_schoolbook(&a, &b));
+
+        let x = BigUint::from(3u8).pow(5000);
+        let y = BigUint::from(7u8).pow(3000);
+        let (q, r) = (&x * &y + BigUint::from(12345u32)).div_rem(&y);
+        assert_eq!(q, x);
+        assert_eq!(r, BigUint::from(12345u32));
+    }
+
+    #[test]
+    fn test_signed() {
+        let a = BigInt::from(-7);
+        let b = BigInt::from(2);
+        assert_eq!(&a / &b, BigInt::from(-3));
+        assert_eq!(&a % &b, BigInt::from(-1));
+        assert_eq!(a.clone().rem_euclid(b.clone()), BigInt::from(1));
+        assert_eq!(&a + &b, BigInt::from(-5));
+        assert_eq!(&b - &a, BigInt::from(9));
+        assert_eq!(&a * &a, BigInt::from(49));
+        assert_eq!(a.pow(3), BigInt::from(-343));
+        assert!(a < b);
+        assert!(BigInt::from(-10) < BigInt::from(-9));
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let a = BigUint::from(2u8).pow(80) * BigUint::from(3u8);
+        let b = BigUint::from(2u8).pow(70) * BigUint::from(5u8);
+        assert_eq!(gcd(a.clone(), b.clone()), BigUint::from(2u8).pow(70));
+        assert_eq!(lcm(a, b), BigUint::from(2u8).pow(80) * BigUint::from(15u8));
+
+        let total = (1..=30u32).map(BigUint::from).lcm().unwrap();
+        assert_eq!(total.to_string(), "2329089562800");
+    }
+}
@@ -0,0 +1,577 @@
+use std::ops::RangeInclusive;
+
+use super::{
+    NumConsts,
+    matrix::{Matrix, ParametricSolution, Solution},
+    rational::Rational,
+};
+
+// Intermediates of the simplex tableau get large quickly, so work one size up
+// from the i64 inputs
+type R = Rational<i128>;
+
+// Each level of branching tightens one bound by at least one. Without upper
+// bounds an integer-free relaxation can be chased forever (2x - 2y = 1), so
+// prune subtrees past this depth rather than overflow the stack
+const MAX_DEPTH: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Objective {
+    MinimiseSum,
+    MaximiseSum,
+    Minimise(Vec<i64>),
+    Maximise(Vec<i64>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IlpSolution {
+    pub x: Vec<i64>,
+    pub objective: i64,
+    /// False if some branch was cut off at the depth limit, so a better
+    /// solution might exist.
+    pub optimal: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IlpError {
+    Infeasible,
+    /// The relaxation is unbounded in the objective direction.
+    Unbounded,
+    /// Branching hit the depth limit before finding any integer solution,
+    /// without ruling one out.
+    DepthLimit,
+}
+
+// State shared by every node of the branch and bound search
+struct Search {
+    best: Option<(i64, Vec<i64>)>,
+    truncated: bool,
+    unbounded: bool,
+}
+
+/// Integer program `A x = b` with `lower <= x <= upper`, solved by branch and
+/// bound on an exact simplex relaxation. Variables default to `0 <= x`.
+#[derive(Debug, Clone)]
+pub struct Ilp {
+    a: Vec<Vec<i64>>,
+    b: Vec<i64>,
+    lower: Vec<i64>,
+    upper: Vec<Option<i64>>,
+    objective: Objective,
+}
+
+impl Ilp {
+    pub fn new(a: Vec<Vec<i64>>, b: Vec<i64>) -> Self {
+        assert_eq!(a.len(), b.len(), "Need one right hand side per row");
+        let vars = a.first().map(Vec::len).unwrap_or(0);
+        assert!(
+            a.iter().all(|row| row.len() == vars),
+            "All rows must be the same length"
+        );
+        Self {
+            a,
+            b,
+            lower: vec![0; vars],
+            upper: vec![None; vars],
+            objective: Objective::MinimiseSum,
+        }
+    }
+
+    pub fn vars(&self) -> usize {
+        self.lower.len()
+    }
+
+    pub fn objective(mut self, objective: Objective) -> Self {
+        if let Objective::Minimise(c) | Objective::Maximise(c) = &objective {
+            assert_eq!(c.len(), self.vars(), "Need one coefficient per variable");
+        }
+        self.objective = objective;
+        self
+    }
+
+    pub fn bounds(mut self, var: usize, lower: i64, upper: Option<i64>) -> Self {
+        self.lower[var] = lower;
+        self.upper[var] = upper;
+        self
+    }
+
+    pub fn upper_bound(mut self, var: usize, upper: i64) -> Self {
+        self.upper[var] = Some(upper);
+        self
+    }
+
+    fn costs(&self) -> Vec<i64> {
+        match &self.objective {
+            Objective::MinimiseSum => vec![1; self.vars()],
+            Objective::MaximiseSum => vec![-1; self.vars()],
+            Objective::Minimise(c) => c.clone(),
+            Objective::Maximise(c) => c.iter().map(|x| -x).collect(),
+        }
+    }
+
+    fn value(&self, x: &[i64]) -> i64 {
+        match &self.objective {
+            Objective::MinimiseSum | Objective::MaximiseSum => x.iter().sum(),
+            Objective::Minimise(c) | Objective::Maximise(c) => {
+                c.iter().zip(x).map(|(ci, xi)| ci * xi).sum()
+            }
+        }
+    }
+
+    fn is_feasible(&self, x: &[i64]) -> bool {
+        let in_bounds = x
+            .iter()
+            .zip(self.lower.iter().zip(&self.upper))
+            .all(|(&xi, (&lo, hi))| xi >= lo && hi.is_none_or(|hi| xi <= hi));
+        in_bounds
+            && self.a.iter().zip(&self.b).all(|(row, &bi)| {
+                let lhs: i128 = row
+                    .iter()
+                    .zip(x)
+                    .map(|(&a, &xi)| a as i128 * xi as i128)
+                    .sum();
+                lhs == bi as i128
+            })
+    }
+
+    /// The best integer solution found. Subtrees deeper than `MAX_DEPTH` are
+    /// pruned, in which case the solution is marked as not proven optimal.
+    pub fn solve(&self) -> Result<IlpSolution, IlpError> {
+        let costs: Vec<R> = self.costs().into_iter().map(to_rational).collect();
+        let mut search = Search {
+            best: None,
+            truncated: false,
+            unbounded: false,
+        };
+        self.branch(
+            &costs,
+            self.lower.clone(),
+            self.upper.clone(),
+            0,
+            &mut search,
+        );
+
+        if search.unbounded {
+            return Err(IlpError::Unbounded);
+        }
+        match search.best {
+            Some((_, x)) => Ok(IlpSolution {
+                objective: self.value(&x),
+                x,
+                optimal: !search.truncated,
+            }),
+            None if search.truncated => Err(IlpError::DepthLimit),
+            None => Err(IlpError::Infeasible),
+        }
+    }
+
+    fn branch(
+        &self,
+        costs: &[R],
+        lower: Vec<i64>,
+        upper: Vec<Option<i64>>,
+        depth: usize,
+        search: &mut Search,
+    ) {
+        let (value, x) = match self.relax(costs, &lower, &upper) {
+            Lp::Optimal(value, x) => (value, x),
+            Lp::Infeasible => return,
+            Lp::Unbounded => {
+                search.unbounded = true;
+                return;
+            }
+        };
+
+        // Integer costs mean integer objective values, so round the bound up
+        if let Some((best_value, _)) = &search.best
+            && value.ceil() >= *best_value as i128
+        {
+            return;
+        }
+
+        match x.iter().position(|xi| !xi.is_integer()) {
+            None => {
+                let x = x.iter().map(|xi| xi.floor() as i64).collect();
+                search.best = Some((value.floor() as i64, x));
+            }
+            Some(_) if depth == MAX_DEPTH => search.truncated = true,
+            Some(j) => {
+                let mut down = upper.clone();
+                down[j] = Some(x[j].floor() as i64);
+                self.branch(costs, lower.clone(), down, depth + 1, search);
+
+                let mut up = lower;
+                up[j] = x[j].ceil() as i64;
+                self.branch(costs, up, upper, depth + 1, search);
+            }
+        }
+    }
+
+    // Substitutes y = x - lower so the simplex only has to deal with y >= 0,
+    // and turns each upper bound into an extra row with a slack variable
+    fn relax(&self, costs: &[R], lower: &[i64], upper: &[Option<i64>]) -> Lp {
+        let n = self.vars();
+        if lower
+            .iter()
+            .zip(upper)
+            .any(|(&lo, hi)| hi.is_some_and(|hi| hi < lo))
+        {
+            return Lp::Infeasible;
+        }
+
+        let bounded: Vec<usize> = (0..n).filter(|&j| upper[j].is_some()).collect();
+        let width = n + bounded.len();
+
+        let mut rows = Vec::new();
+        let mut rhs = Vec::new();
+        for (row, &bi) in self.a.iter().zip(&self.b) {
+            let shift: i128 = row
+                .iter()
+                .zip(lower)
+                .map(|(&a, &lo)| a as i128 * lo as i128)
+                .sum();
+            let mut r: Vec<R> = row.iter().map(|&a| to_rational(a)).collect();
+            r.resize(width, R::ZERO);
+            rows.push(r);
+            rhs.push(R::from(bi as i128 - shift));
+        }
+        for (k, &j) in bounded.iter().enumerate() {
+            let mut r = vec![R::ZERO; width];
+            r[j] = R::ONE;
+            r[n + k] = R::ONE;
+            rows.push(r);
+            rhs.push(R::from(upper[j].unwrap() as i128 - lower[j] as i128));
+        }
+
+        let mut c = costs.to_vec();
+        c.resize(width, R::ZERO);
+
+        match simplex(rows, rhs, &c) {
+            Lp::Optimal(_, y) => {
+                let x: Vec<R> = y[..n]
+                    .iter()
+                    .zip(lower)
+                    .map(|(yi, &lo)| *yi + to_rational(lo))
+                    .collect();
+                let value = x.iter().zip(costs).map(|(xi, ci)| *xi * *ci).sum();
+                Lp::Optimal(value, x)
+            }
+            other => other,
+        }
+    }
+
+    /// Solves `A x = b` over the rationals and reports which variables are
+    /// left free, as indices into `x`. `None` if the system is inconsistent.
+    pub fn free_variables(&self) -> Option<Vec<usize>> {
+        let m = Matrix::from_rows(self.a.clone()).map(|&x| to_rational(x));
+        let b: Vec<R> = self.b.iter().map(|&x| to_rational(x)).collect();
+        match m.solve(&b) {
+            Solution::Inconsistent => None,
+            Solution::Unique(_) => Some(Vec::new()),
+            Solution::Parametric(p) => Some(p.free),
+        }
+    }
+
+    /// Every integer solution within the bounds, found by trying each value
+    /// of the free variables left after elimination. The range tried for each
+    /// free variable comes from its bounds, tightened by the relaxation.
+    /// Returns `None` if some free variable is unbounded.
+    pub fn enumerate(&self) -> Option<Vec<Vec<i64>>> {
+        let m = Matrix::from_rows(self.a.clone()).map(|&x| to_rational(x));
+        let b: Vec<R> = self.b.iter().map(|&x| to_rational(x)).collect();
+        let parametric = match m.solve(&b) {
+            Solution::Inconsistent => return Some(Vec::new()),
+            Solution::Unique(x) => {
+                let x: Option<Vec<i64>> = x
+                    .iter()
+                    .map(|xi| xi.to_integer().map(|v| v as i64))
+                    .collect();
+                return Some(x.filter(|x| self.is_feasible(x)).into_iter().collect());
+            }
+            Solution::Parametric(p) => p,
+        };
+
+        let mut ranges = Vec::new();
+        for &f in &parametric.free {
+            let mut c = vec![R::ZERO; self.vars()];
+            c[f] = R::ONE;
+            let Lp::Optimal(lo, _) = self.relax(&c, &self.lower, &self.upper) else {
+                // Either infeasible, or unbounded below which cannot happen
+                // with finite lower bounds
+                return Some(Vec::new());
+            };
+            c[f] = -R::ONE;
+            let Lp::Optimal(neg_hi, _) = self.relax(&c, &self.lower, &self.upper) else {
+                return None;
+            };
+            ranges.push(lo.ceil() as i64..=(-neg_hi).floor() as i64);
+        }
+
+        let mut solutions = Vec::new();
+        let mut params = vec![R::ZERO; ranges.len()];
+        self.enumerate_params(&parametric, &ranges, &mut params, 0, &mut solutions);
+        Some(solutions)
+    }
+
+    fn enumerate_params(
+        &self,
+        parametric: &ParametricSolution<i128>,
+        ranges: &[RangeInclusive<i64>],
+        params: &mut [R],
+        depth: usize,
+        solutions: &mut Vec<Vec<i64>>,
+    ) {
+        if depth == ranges.len() {
+            let x = parametric.evaluate(params);
+            let x: Option<Vec<i64>> = x
+                .iter()
+                .map(|xi| xi.to_integer().map(|v| v as i64))
+                .collect();
+            if let Some(x) = x.filter(|x| self.is_feasible(x)) {
+                solutions.push(x);
+            }
+            return;
+        }
+        for t in ranges[depth].clone() {
+            params[depth] = to_rational(t);
+            self.enumerate_params(parametric, ranges, params, depth + 1, solutions);
+        }
+    }
+}
+
+/// Minimum of `sum(x)` over non-negative integer solutions of `A x = b`.
+/// `None` if there is none, or the search gave up before finding one.
+pub fn min_sum_solution(a: Vec<Vec<i64>>, b: Vec<i64>) -> Option<Vec<i64>> {
+    Ilp::new(a, b).solve().ok().map(|solution| solution.x)
+}
+
+fn to_rational(x: i64) -> R {
+    R::from(x as i128)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Lp {
+    Infeasible,
+    Unbounded,
+    Optimal(R, Vec<R>),
+}
+
+// Two phase simplex minimising `c.x` subject to `rows x = rhs`, `x >= 0`.
+// Bland's rule keeps it from cycling on degenerate problems.
+fn simplex(mut rows: Vec<Vec<R>>, mut rhs: Vec<R>, c: &[R]) -> Lp {
+    let m = rows.len();
+    let n = c.len();
+
+    for (row, bi) in rows.iter_mut().zip(rhs.iter_mut()) {
+        if bi.is_negative() {
+            row.iter_mut().for_each(|x| *x = -*x);
+            *bi = -*bi;
+        }
+    }
+
+    // Artificial variables n..n+m start as the basis
+    for (i, row) in rows.iter_mut().enumerate() {
+        row.resize(n + m, R::ZERO);
+        row[n + i] = R::ONE;
+    }
+    let mut tableau = Tableau {
+        rows,
+        rhs,
+        basis: (n..n + m).collect(),
+    };
+
+    let mut phase1 = vec![R::ZERO; n + m];
+    phase1[n..].iter_mut().for_each(|x| *x = R::ONE);
+    if !tableau.optimise(&phase1, n + m) {
+        unreachable!("Phase one objective is bounded below by zero");
+    }
+    if tableau.objective(&phase1) != R::ZERO {
+        return Lp::Infeasible;
+    }
+
+    // Pivot any artificial variables left in the basis out, if possible.
+    // Rows where that is not possible are redundant and stay at zero.
+    for i in 0..m {
+        if tableau.basis[i] >= n
+            && let Some(j) = (0..n).find(|&j| !tableau.rows[i][j].is_zero())
+        {
+            tableau.pivot(i, j);
+        }
+    }
+
+    let mut phase2 = c.to_vec();
+    phase2.resize(n + m, R::ZERO);
+    if !tableau.optimise(&phase2, n) {
+        return Lp::Unbounded;
+    }
+
+    let mut x = vec![R::ZERO; n];
+    for (i, &j) in tableau.basis.iter().enumerate() {
+        if j < n {
+            x[j] = tableau.rhs[i];
+        }
+    }
+    Lp::Optimal(tableau.objective(&phase2), x)
+}
+
+struct Tableau {
+    rows: Vec<Vec<R>>,
+    rhs: Vec<R>,
+    basis: Vec<usize>,
+}
+
+impl Tableau {
+    fn objective(&self, c: &[R]) -> R {
+        self.basis
+            .iter()
+            .zip(&self.rhs)
+            .map(|(&j, bi)| c[j] * *bi)
+            .sum()
+    }
+
+    fn pivot(&mut self, row: usize, col: usize) {
+        let inv = self.rows[row][col].recip();
+        self.rows[row].iter_mut().for_each(|x| *x *= inv);
+        self.rhs[row] *= inv;
+
+        for i in 0..self.rows.len() {
+            let factor = self.rows[i][col];
+            if i == row || factor.is_zero() {
+                continue;
+            }
+            for j in 0..self.rows[i].len() {
+                let delta = factor * self.rows[row][j];
+                self.rows[i][j] -= delta;
+            }
+            let delta = factor * self.rhs[row];
+            self.rhs[i] -= delta;
+        }
+        self.basis[row] = col;
+    }
+
+    // Only columns below `allowed` may enter the basis. Returns false if the
+    // objective is unbounded.
+    fn optimise(&mut self, c: &[R], allowed: usize) -> bool {
+        loop {
+            let reduced = |j: usize| {
+                c[j] - self
+                    .basis
+                    .iter()
+                    .zip(&self.rows)
+                    .map(|(&b, row)| c[b] * row[j])
+                    .sum::<R>()
+            };
+            let Some(col) = (0..allowed).find(|&j| reduced(j).is_negative()) else {
+                return true;
+            };
+
+            let leaving = (0..self.rows.len())
+                .filter(|&i| self.rows[i][col] > R::ZERO)
+                .min_by(|&i, &k| {
+                    let ri = self.rhs[i] / self.rows[i][col];
+                    let rk = self.rhs[k] / self.rows[k][col];
+                    ri.cmp(&rk).then(self.basis[i].cmp(&self.basis[k]))
+                });
+            let Some(row) = leaving else {
+                return false;
+            };
+            self.pivot(row, col);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_min_sum() {
+        // Button presses: each column is a button adding 1 to the counters it
+        // touches, find the fewest presses reaching the targets
+        let a = vec![
+            vec![0, 0, 0, 0, 1, 1],
+            vec![0, 1, 0, 0, 0, 1],
+            vec![0, 0, 1, 1, 1, 0],
+            vec![1, 1, 0, 1, 0, 0],
+        ];
+        let b = vec![3, 5, 4, 7];
+        let x = min_sum_solution(a.clone(), b.clone()).unwrap();
+        assert_eq!(x.iter().sum::<i64>(), 10);
+        assert!(Ilp::new(a, b).is_feasible(&x));
+    }
+
+    #[test]
+    fn test_infeasible() {
+        // 2x = 3 has a rational solution but no integer one
+        assert_eq!(min_sum_solution(vec![vec![2]], vec![3]), None);
+        assert_eq!(min_sum_solution(vec![vec![1, 1]], vec![-1]), None);
+        // Feasible and unbounded relaxation with no integer point
+        assert_eq!(min_sum_solution(vec![vec![2, -2]], vec![1]), None);
+        assert_eq!(
+            Ilp::new(vec![vec![2, -2]], vec![1]).solve(),
+            Err(IlpError::DepthLimit)
+        );
+        assert_eq!(
+            Ilp::new(vec![vec![2]], vec![3]).solve(),
+            Err(IlpError::Infeasible)
+        );
+    }
+
+    #[test]
+    fn test_depth_limit_keeps_best() {
+        // 2x - 2y + z = 1 minimising z: x <= 0 quickly gives z = 1, while
+        // x >= 1 chases z = 0 forever and gets cut off
+        let solution = Ilp::new(vec![vec![2, -2, 1]], vec![1])
+            .objective(Objective::Minimise(vec![0, 0, 1]))
+            .solve()
+            .unwrap();
+        assert_eq!(solution.objective, 1);
+        assert!(!solution.optimal);
+    }
+
+    #[test]
+    fn test_unbounded() {
+        // x - y = 0 can grow forever
+        let ilp = Ilp::new(vec![vec![1, -1]], vec![0]).objective(Objective::MaximiseSum);
+        assert_eq!(ilp.solve(), Err(IlpError::Unbounded));
+    }
+
+    #[test]
+    fn test_objective_and_bounds() {
+        // x + y + z = 10
+        let ilp = Ilp::new(vec![vec![1, 1, 1]], vec![10]);
+
+        let max = ilp
+            .clone()
+            .objective(Objective::Maximise(vec![3, 2, 1]))
+            .upper_bound(0, 2)
+            .solve()
+            .unwrap();
+        assert_eq!(max.x, vec![2, 8, 0]);
+        assert_eq!(max.objective, 22);
+        assert!(max.optimal);
+
+        let min = ilp
+            .objective(Objective::Minimise(vec![1, 2, 5]))
+            .bounds(0, 1, Some(4))
+            .bounds(2, 3, None)
+            .solve()
+            .unwrap();
+        assert_eq!(min.x, vec![4, 3, 3]);
+        assert_eq!(min.objective, 25);
+    }
+
+    #[test]
+    fn test_enumerate() {
+        // x + 2y = 6 with free y
+        let ilp = Ilp::new(vec![vec![1, 2]], vec![6]);
+        assert_eq!(ilp.free_variables(), Some(vec![1]));
+        assert_eq!(
+            ilp.enumerate(),
+            Some(vec![vec![6, 0], vec![4, 1], vec![2, 2], vec![0, 3]])
+        );
+
+        let unbounded = Ilp::new(vec![vec![1, -1]], vec![0]);
+        assert_eq!(unbounded.enumerate(), None);
+    }
+}
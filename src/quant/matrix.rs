@@ -0,0 +1,627 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Index, IndexMut, Mul, Rem},
+};
+
+use super::{
+    NumConsts,
+    rational::{Rational, SignedInt},
+};
+
+/// Dense row-major matrix, indexed by `(row, col)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    items: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    pub fn new(rows: usize, cols: usize, items: Vec<T>) -> Self {
+        assert_eq!(
+            items.len(),
+            rows * cols,
+            "Matrix needs exactly rows * cols items"
+        );
+        Self { rows, cols, items }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map(Vec::len).unwrap_or(0);
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "All matrix rows must be the same length"
+        );
+        Self::new(height, width, rows.into_iter().flatten().collect())
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.rows == self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.rows && col < self.cols).then(|| &self.items[row * self.cols + col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.items[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.items[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.items.chunks(self.cols.max(1)).take(self.rows)
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a != b {
+            for col in 0..self.cols {
+                self.items.swap(a * self.cols + col, b * self.cols + col);
+            }
+        }
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Matrix<U> {
+        Matrix::new(self.rows, self.cols, self.items.iter().map(f).collect())
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    pub fn fill(rows: usize, cols: usize, val: T) -> Self {
+        Self::new(rows, cols, vec![val; rows * cols])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let items = (0..self.cols)
+            .flat_map(|col| (0..self.rows).map(move |row| (row, col)))
+            .map(|(row, col)| self[(row, col)].clone())
+            .collect();
+        Self::new(self.cols, self.rows, items)
+    }
+
+    /// Appends `column` as an extra rightmost column.
+    pub fn augment(&self, column: &[T]) -> Self {
+        assert_eq!(column.len(), self.rows);
+        let items = self
+            .iter_rows()
+            .zip(column)
+            .flat_map(|(row, extra)| row.iter().chain(std::iter::once(extra)).cloned())
+            .collect();
+        Self::new(self.rows, self.cols + 1, items)
+    }
+}
+
+impl<T: Clone + NumConsts> Matrix<T> {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::fill(rows, cols, T::ZERO)
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut m = Self::zeros(size, size);
+        for i in 0..size {
+            m[(i, i)] = T::ONE;
+        }
+        m
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Matrix index out of bounds"
+        );
+        &self.items[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(
+            row < self.rows && col < self.cols,
+            "Matrix index out of bounds"
+        );
+        &mut self.items[row * self.cols + col]
+    }
+}
+
+impl<T: Display> Display for Matrix<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.iter_rows().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            for (j, item) in row.iter().enumerate() {
+                if j != 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{item}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    fn product(&self, rhs: &Self, reduce: impl Fn(T) -> T) -> Self {
+        assert_eq!(
+            self.cols, rhs.rows,
+            "Matrix dimensions don't match for multiplication"
+        );
+        let mut out = Self::zeros(self.rows, rhs.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = &self[(i, k)];
+                for j in 0..rhs.cols {
+                    let sum = out[(i, j)].clone() + a.clone() * rhs[(k, j)].clone();
+                    out[(i, j)] = reduce(sum);
+                }
+            }
+        }
+        out
+    }
+
+    fn vec_product(&self, v: &[T], reduce: impl Fn(T) -> T) -> Vec<T> {
+        assert_eq!(self.cols, v.len());
+        self.iter_rows()
+            .map(|row| {
+                row.iter()
+                    .zip(v)
+                    .fold(T::ZERO, |acc, (a, b)| reduce(acc + a.clone() * b.clone()))
+            })
+            .collect()
+    }
+
+    pub fn mul_vec(&self, v: &[T]) -> Vec<T> {
+        self.vec_product(v, |x| x)
+    }
+
+    fn power(&self, mut exp: u64, reduce: impl Fn(T) -> T + Copy) -> Self {
+        assert!(self.is_square(), "Only square matrices have powers");
+        let mut base = self.map(|x| reduce(x.clone()));
+        let mut acc = Self::identity(self.rows).map(|x| reduce(x.clone()));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.product(&base, reduce);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.product(&base, reduce);
+            }
+        }
+        acc
+    }
+
+    pub fn pow(&self, exp: u64) -> Self {
+        self.power(exp, |x| x)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T> + Rem<Output = T>,
+{
+    /// Product with every entry reduced modulo `m` as it goes, so entries
+    /// stay below `m` and products below `m^2`.
+    pub fn mul_mod(&self, rhs: &Self, m: T) -> Self {
+        self.product(rhs, |x| x % m.clone())
+    }
+
+    pub fn mul_vec_mod(&self, v: &[T], m: T) -> Vec<T> {
+        self.vec_product(v, |x| x % m.clone())
+    }
+
+    pub fn pow_mod(&self, exp: u64, m: T) -> Self {
+        self.power(exp, |x| x % m.clone())
+    }
+}
+
+impl<T> Mul<&Matrix<T>> for &Matrix<T>
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: &Matrix<T>) -> Self::Output {
+        self.product(rhs, |x| x)
+    }
+}
+
+impl<T> Mul for Matrix<T>
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, rhs: Matrix<T>) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+/// Fixed size square matrix living on the stack, for hot loops where the
+/// dimension is known up front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SMatrix<T, const N: usize>(pub [[T; N]; N]);
+
+impl<T, const N: usize> SMatrix<T, N>
+where
+    T: Copy + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    pub fn zeros() -> Self {
+        Self([[T::ZERO; N]; N])
+    }
+
+    pub fn identity() -> Self {
+        let mut m = Self::zeros();
+        for i in 0..N {
+            m.0[i][i] = T::ONE;
+        }
+        m
+    }
+
+    fn product(&self, rhs: &Self, reduce: impl Fn(T) -> T) -> Self {
+        let mut out = Self::zeros();
+        for i in 0..N {
+            for k in 0..N {
+                for j in 0..N {
+                    out.0[i][j] = reduce(out.0[i][j] + self.0[i][k] * rhs.0[k][j]);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn mul_vec(&self, v: &[T; N]) -> [T; N] {
+        std::array::from_fn(|i| (0..N).fold(T::ZERO, |acc, j| acc + self.0[i][j] * v[j]))
+    }
+
+    fn power(&self, mut exp: u64, reduce: impl Fn(T) -> T + Copy) -> Self {
+        let mut base = Self(self.0.map(|row| row.map(reduce)));
+        let mut acc = Self(Self::identity().0.map(|row| row.map(reduce)));
+        while exp > 0 {
+            if exp & 1 == 1 {
+                acc = acc.product(&base, reduce);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.product(&base, reduce);
+            }
+        }
+        acc
+    }
+
+    pub fn pow(&self, exp: u64) -> Self {
+        self.power(exp, |x| x)
+    }
+}
+
+impl<T, const N: usize> SMatrix<T, N>
+where
+    T: Copy + NumConsts + Add<Output = T> + Mul<Output = T> + Rem<Output = T>,
+{
+    pub fn mul_mod(&self, rhs: &Self, m: T) -> Self {
+        self.product(rhs, |x| x % m)
+    }
+
+    pub fn pow_mod(&self, exp: u64, m: T) -> Self {
+        self.power(exp, |x| x % m)
+    }
+}
+
+impl<T, const N: usize> Mul for SMatrix<T, N>
+where
+    T: Copy + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.product(&rhs, |x| x)
+    }
+}
+
+impl<T, const N: usize> From<SMatrix<T, N>> for Matrix<T> {
+    fn from(m: SMatrix<T, N>) -> Self {
+        Matrix::new(N, N, m.0.into_iter().flatten().collect())
+    }
+}
+
+/// Result of `Matrix::rref`: the reduced matrix and the column of each pivot,
+/// one per non-zero row.
+#[derive(Debug, Clone)]
+pub struct Echelon<T> {
+    pub matrix: Matrix<Rational<T>>,
+    pub pivots: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T> {
+    Inconsistent,
+    Unique(Vec<Rational<T>>),
+    Parametric(ParametricSolution<T>),
+}
+
+/// Every solution is `particular + sum(t_i * directions[i])`, where `t_i` is
+/// the value chosen for variable `free[i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParametricSolution<T> {
+    pub particular: Vec<Rational<T>>,
+    pub free: Vec<usize>,
+    pub directions: Vec<Vec<Rational<T>>>,
+}
+
+impl<T: SignedInt> ParametricSolution<T> {
+    pub fn evaluate(&self, params: &[Rational<T>]) -> Vec<Rational<T>> {
+        assert_eq!(params.len(), self.free.len());
+        let mut x = self.particular.clone();
+        for (t, direction) in params.iter().zip(&self.directions) {
+            for (xi, di) in x.iter_mut().zip(direction) {
+                *xi += t.clone() * di.clone();
+            }
+        }
+        x
+    }
+}
+
+impl<T: SignedInt> Matrix<T> {
+    pub fn to_rational(&self) -> Matrix<Rational<T>> {
+        self.map(|x| Rational::from_integer(x.clone()))
+    }
+}
+
+impl<T: SignedInt> Matrix<Rational<T>> {
+    /// Reduced row echelon form, only choosing pivots from the first
+    /// `pivot_cols` columns so augmented systems can be reduced in place.
+    fn reduce(&self, pivot_cols: usize) -> (Echelon<T>, bool, Rational<T>) {
+        let mut m = self.clone();
+        let mut pivots = Vec::new();
+        let mut swapped = false;
+        let mut scale = Rational::ONE;
+
+        for col in 0..pivot_cols {
+            let row = pivots.len();
+            if row == m.rows {
+                break;
+            }
+            let Some(pivot_row) = (row..m.rows).find(|&r| !m[(r, col)].is_zero()) else {
+                continue;
+            };
+            if pivot_row != row {
+                m.swap_rows(pivot_row, row);
+                swapped = !swapped;
+            }
+
+            let pivot = m[(row, col)].clone();
+            scale *= pivot.clone();
+            let inv = pivot.recip();
+            for x in m.row_mut(row) {
+                *x = x.clone() * inv.clone();
+            }
+
+            for other in 0..m.rows {
+                let factor = m[(other, col)].clone();
+                if other == row || factor.is_zero() {
+                    continue;
+                }
+                for c in col..m.cols {
+                    let delta = factor.clone() * m[(row, c)].clone();
+                    m[(other, c)] -= delta;
+                }
+            }
+
+            pivots.push(col);
+        }
+
+        (Echelon { matrix: m, pivots }, swapped, scale)
+    }
+
+    pub fn rref(&self) -> Echelon<T> {
+        self.reduce(self.cols).0
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rref().pivots.len()
+    }
+
+    /// `None` for non-square matrices.
+    pub fn determinant(&self) -> Option<Rational<T>> {
+        if !self.is_square() {
+            return None;
+        }
+        let (echelon, swapped, scale) = self.reduce(self.cols);
+        Some(if echelon.pivots.len() < self.rows {
+            Rational::ZERO
+        } else if swapped {
+            -scale
+        } else {
+            scale
+        })
+    }
+
+    pub fn inverse(&self) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+        let n = self.rows;
+        let identity = Self::identity(n);
+        let items = self
+            .iter_rows()
+            .zip(identity.iter_rows())
+            .flat_map(|(row, id)| row.iter().chain(id).cloned())
+            .collect();
+        let (echelon, _, _) = Self::new(n, 2 * n, items).reduce(n);
+        if echelon.pivots.len() < n {
+            return None;
+        }
+        let items = echelon
+            .matrix
+            .iter_rows()
+            .flat_map(|row| row[n..].iter().cloned())
+            .collect();
+        Some(Self::new(n, n, items))
+    }
+
+    /// Solves `self * x = b`.
+    pub fn solve(&self, b: &[Rational<T>]) -> Solution<T> {
+        let (echelon, _, _) = self.augment(b).reduce(self.cols);
+        let m = &echelon.matrix;
+        let rank = echelon.pivots.len();
+
+        // A zero row with a non-zero right hand side
+        if (rank..m.rows).any(|row| !m[(row, self.cols)].is_zero()) {
+            return Solution::Inconsistent;
+        }
+
+        let mut particular = vec![Rational::ZERO; self.cols];
+        for (row, &col) in echelon.pivots.iter().enumerate() {
+            particular[col] = m[(row, self.cols)].clone();
+        }
+
+        if rank == self.cols {
+            return Solution::Unique(particular);
+        }
+
+        let free: Vec<usize> = (0..self.cols)
+            .filter(|col| !echelon.pivots.contains(col))
+            .collect();
+        let directions = free
+            .iter()
+            .map(|&f| {
+                let mut direction = vec![Rational::ZERO; self.cols];
+                direction[f] = Rational::ONE;
+                for (row, &col) in echelon.pivots.iter().enumerate() {
+                    direction[col] = -m[(row, f)].clone();
+                }
+                direction
+            })
+            .collect();
+
+        Solution::Parametric(ParametricSolution {
+            particular,
+            free,
+            directions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type R = Rational<i64>;
+
+    fn rat(rows: Vec<Vec<i64>>) -> Matrix<R> {
+        Matrix::from_rows(rows).to_rational()
+    }
+
+    fn ints(xs: &[i64]) -> Vec<R> {
+        xs.iter().copied().map(R::from).collect()
+    }
+
+    #[test]
+    fn test_pow() {
+        let fib = Matrix::from_rows(vec![vec![1u64, 1], vec![1, 0]]);
+        assert_eq!(fib.pow(0), Matrix::identity(2));
+        assert_eq!(fib.pow(10)[(0, 1)], 55);
+        assert_eq!(fib.pow(90)[(0, 1)], 2880067194370816120);
+        assert_eq!(
+            fib.pow_mod(1_000_000_000_000_000, 1_000_000_007)[(0, 1)],
+            648325137
+        );
+
+        let a = Matrix::from_rows(vec![vec![1i32, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::from_rows(vec![vec![7, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!(
+            &a * &b,
+            Matrix::from_rows(vec![vec![58, 64], vec![139, 154]])
+        );
+        assert_eq!(a.mul_vec(&[1, 0, -1]), vec![-2, -2]);
+    }
+
+    #[test]
+    fn test_smatrix() {
+        let fib = SMatrix([[1u64, 1], [1, 0]]);
+        assert_eq!(fib.pow(90).0[0][1], 2880067194370816120);
+        assert_eq!(
+            fib.pow_mod(1_000_000_000_000_000, 1_000_000_007).0[0][1],
+            648325137
+        );
+        assert_eq!(fib * SMatrix::identity(), fib);
+        assert_eq!(fib.mul_vec(&[1, 0]), [1, 1]);
+        assert_eq!(Matrix::from(fib.pow(5)), Matrix::from(fib).pow(5));
+    }
+
+    #[test]
+    fn test_determinant_rank() {
+        let m = rat(vec![vec![2, 0, 1], vec![1, 3, 2], vec![1, 1, 2]]);
+        assert_eq!(m.determinant(), Some(R::from(6)));
+        assert_eq!(m.rank(), 3);
+
+        let swapped = rat(vec![vec![0, 1], vec![1, 0]]);
+        assert_eq!(swapped.determinant(), Some(R::from(-1)));
+
+        let singular = rat(vec![vec![1, 2], vec![2, 4]]);
+        assert_eq!(singular.determinant(), Some(R::ZERO));
+        assert_eq!(singular.rank(), 1);
+        assert_eq!(singular.inverse(), None);
+
+        assert_eq!(rat(vec![vec![1, 2, 3]]).determinant(), None);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let m = rat(vec![vec![4, 7], vec![2, 6]]);
+        let inv = m.inverse().unwrap();
+        assert_eq!(inv[(0, 0)], R::new(3, 5));
+        assert_eq!(inv[(0, 1)], R::new(-7, 10));
+        assert_eq!(inv[(1, 0)], R::new(-1, 5));
+        assert_eq!(inv[(1, 1)], R::new(2, 5));
+    }
+
+    #[test]
+    fn test_solve_unique() {
+        // Claw machine style: 94a + 22b = 8400, 34a + 67b = 5400
+        let m = rat(vec![vec![94, 22], vec![34, 67]]);
+        assert_eq!(
+            m.solve(&ints(&[8400, 5400])),
+            Solution::Unique(ints(&[80, 40]))
+        );
+
+        let m = rat(vec![vec![1, 1], vec![1, 1]]);
+        assert_eq!(m.solve(&ints(&[1, 2])), Solution::Inconsistent);
+    }
+
+    #[test]
+    fn test_solve_parametric() {
+        let m = rat(vec![vec![1, 1, 1], vec![0, 1, 2]]);
+        let b = ints(&[6, 8]);
+        let Solution::Parametric(solution) = m.solve(&b) else {
+            panic!("Expected a parametric solution");
+        };
+        assert_eq!(solution.free, vec![2]);
+
+        for t in -3..=3 {
+            let x = solution.evaluate(&[R::from(t)]);
+            assert_eq!(x[2], R::from(t));
+            for (row, bi) in m.iter_rows().zip(&b) {
+                let lhs: R = row.iter().zip(&x).map(|(a, xi)| *a * *xi).sum();
+                assert_eq!(lhs, *bi);
+            }
+        }
+    }
+}
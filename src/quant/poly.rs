@@ -0,0 +1,261 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Mul, Sub},
+};
+
+use super::{
+    NumConsts,
+    rational::{Rational, SignedInt},
+};
+
+/// Repeated forward differences of `samples`, starting with the samples
+/// themselves and stopping once a row is all zero or only one item long.
+pub fn differences<T>(samples: &[T]) -> Vec<Vec<T>>
+where
+    T: Clone + Sub<Output = T> + NumConsts + PartialEq,
+{
+    let mut rows = vec![samples.to_vec()];
+    loop {
+        let last = rows.last().unwrap();
+        if last.len() <= 1 || last.iter().all(|x| *x == T::ZERO) {
+            return rows;
+        }
+        let next = last
+            .windows(2)
+            .map(|w| w[1].clone() - w[0].clone())
+            .collect();
+        rows.push(next);
+    }
+}
+
+/// The degree of the polynomial generating `samples`, if the samples are
+/// enough to tell: the differences must settle to a constant with at least
+/// one spare sample confirming it.
+pub fn degree<T>(samples: &[T]) -> Option<usize>
+where
+    T: Clone + Sub<Output = T> + NumConsts + PartialEq,
+{
+    differences(samples)
+        .iter()
+        .position(|row| row.len() >= 2 && row.iter().all(|x| *x == row[0]))
+}
+
+/// The value following the last sample.
+pub fn extrapolate_forward<T>(samples: &[T]) -> T
+where
+    T: Clone + Add<Output = T> + Sub<Output = T> + NumConsts + PartialEq,
+{
+    differences(samples)
+        .iter()
+        .filter_map(|row| row.last().cloned())
+        .fold(T::ZERO, |acc, x| acc + x)
+}
+
+/// The value preceding the first sample.
+pub fn extrapolate_backward<T>(samples: &[T]) -> T
+where
+    T: Clone + Add<Output = T> + Sub<Output = T> + NumConsts + PartialEq,
+{
+    differences(samples)
+        .iter()
+        .rev()
+        .filter_map(|row| row.first().cloned())
+        .fold(T::ZERO, |acc, x| x - acc)
+}
+
+/// The value at index `k` of the sequence whose first samples are `samples`,
+/// by Newton's forward difference formula. `k` may be negative or far past
+/// the end of the samples.
+pub fn value_at<T>(samples: &[T], k: i64) -> T
+where
+    T: SignedInt + From<i64>,
+{
+    let k = T::from(k);
+    let mut binomial = T::ONE;
+    let mut total = T::ZERO;
+    // The final row of differences may be all zero, and its binomial could
+    // overflow for no reason
+    let rows = differences(samples);
+    let rows = rows
+        .iter()
+        .take_while(|row| row.iter().any(|x| *x != T::ZERO));
+    for (j, row) in rows.enumerate() {
+        if j > 0 {
+            // C(k, j) = C(k, j - 1) * (k - j + 1) / j, always exact
+            let j = T::from(j as i64);
+            binomial = binomial * (k.clone() - j.clone() + T::ONE) / j;
+        }
+        total = total + binomial.clone() * row[0].clone();
+    }
+    total
+}
+
+/// Coefficients in increasing order of power, so `coeffs[i]` goes with `x^i`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial<T> {
+    coeffs: Vec<T>,
+}
+
+impl<T: NumConsts + PartialEq> Polynomial<T> {
+    pub fn new(mut coeffs: Vec<T>) -> Self {
+        while coeffs.last() == Some(&T::ZERO) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    pub fn coeffs(&self) -> &[T] {
+        &self.coeffs
+    }
+
+    /// `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+}
+
+impl<T> Polynomial<T>
+where
+    T: Clone + Add<Output = T> + Mul<Output = T> + NumConsts,
+{
+    pub fn eval(&self, x: T) -> T {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(T::ZERO, |acc, c| acc * x.clone() + c.clone())
+    }
+}
+
+impl<T: SignedInt> Polynomial<Rational<T>> {
+    /// Exact Lagrange interpolation through `points`, which must have
+    /// distinct x values.
+    pub fn interpolate(points: &[(T, T)]) -> Self {
+        let mut coeffs = vec![Rational::ZERO; points.len()];
+
+        for (i, (xi, yi)) in points.iter().enumerate() {
+            // Build prod_{j != i} (x - xj) / (xi - xj) one factor at a time
+            let mut basis = vec![Rational::from_integer(yi.clone())];
+            for (j, (xj, _)) in points.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                let denom = Rational::from_integer(xi.clone() - xj.clone());
+                assert!(!denom.is_zero(), "Interpolation points must be distinct");
+                let shift = -Rational::from_integer(xj.clone());
+
+                let mut next = vec![Rational::ZERO; basis.len() + 1];
+                for (power, c) in basis.iter().enumerate() {
+                    let c = c.clone() / denom.clone();
+                    next[power + 1] += c.clone();
+                    next[power] += c * shift.clone();
+                }
+                basis = next;
+            }
+            for (acc, c) in coeffs.iter_mut().zip(basis) {
+                *acc += c;
+            }
+        }
+
+        Self::new(coeffs)
+    }
+
+    /// The quadratic through samples taken at steps 0, 1 and 2. For samples
+    /// taken every `period` steps starting at `offset`, evaluate at
+    /// `(target - offset) / period`.
+    pub fn fit_quadratic(samples: [T; 3]) -> Self {
+        let [y0, y1, y2] = samples.map(Rational::from_integer);
+        let two = Rational::from_integer(T::ONE + T::ONE);
+        let a = (y2 - y1.clone() - y1.clone() + y0.clone()) / two;
+        let b = y1 - y0.clone() - a.clone();
+        Self::new(vec![y0, b, a])
+    }
+
+    pub fn eval_integer(&self, x: T) -> Rational<T> {
+        self.eval(Rational::from_integer(x))
+    }
+}
+
+impl<T: Display + NumConsts + PartialEq> Display for Polynomial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.coeffs.is_empty() {
+            return write!(f, "0");
+        }
+        let mut first = true;
+        for (power, c) in self.coeffs.iter().enumerate().rev() {
+            if *c == T::ZERO {
+                continue;
+            }
+            if !first {
+                write!(f, " + ")?;
+            }
+            first = false;
+            match power {
+                0 => write!(f, "{c}")?,
+                1 => write!(f, "({c})x")?,
+                _ => write!(f, "({c})x^{power}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    type R = Rational<i64>;
+
+    #[test]
+    fn test_extrapolate() {
+        assert_eq!(extrapolate_forward(&[0i64, 3, 6, 9, 12, 15]), 18);
+        assert_eq!(extrapolate_forward(&[1i64, 3, 6, 10, 15, 21]), 28);
+        assert_eq!(extrapolate_forward(&[10i64, 13, 16, 21, 30, 45]), 68);
+
+        assert_eq!(extrapolate_backward(&[0i64, 3, 6, 9, 12, 15]), -3);
+        assert_eq!(extrapolate_backward(&[1i64, 3, 6, 10, 15, 21]), 0);
+        assert_eq!(extrapolate_backward(&[10i64, 13, 16, 21, 30, 45]), 5);
+    }
+
+    #[test]
+    fn test_degree() {
+        assert_eq!(degree(&[5i64, 5, 5]), Some(0));
+        assert_eq!(degree(&[0i64, 3, 6, 9]), Some(1));
+        assert_eq!(degree(&[1i64, 4, 9, 16, 25]), Some(2));
+        // Three samples always fit a quadratic, so there's nothing to confirm
+        assert_eq!(degree(&[1i64, 4, 9]), None);
+    }
+
+    #[test]
+    fn test_value_at() {
+        let squares = [0i64, 1, 4, 9];
+        assert_eq!(value_at(&squares, 1000), 1_000_000);
+        assert_eq!(value_at(&squares, -7), 49);
+        assert_eq!(
+            value_at(&[1i64, 3, 6, 10, 15, 21], 26501365),
+            26501366 * 26501367 / 2
+        );
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // 2x^2 - 3x + 1
+        let poly = Polynomial::interpolate(&[(-1i64, 6), (0, 1), (2, 3), (5, 36)]);
+        assert_eq!(poly.coeffs(), &[R::from(1), R::from(-3), R::from(2)]);
+        assert_eq!(poly.degree(), Some(2));
+        assert_eq!(poly.eval_integer(10), R::from(171));
+
+        // x / 2 is not integral
+        let half = Polynomial::interpolate(&[(0i64, 0), (2, 1)]);
+        assert_eq!(half.eval_integer(3), R::new(3, 2));
+    }
+
+    #[test]
+    fn test_fit_quadratic() {
+        // Samples of 3x^2 + 2x + 1 at x = 65, 196, 327
+        let f = |x: i64| 3 * x * x + 2 * x + 1;
+        let poly = Polynomial::fit_quadratic([f(65), f(196), f(327)]);
+        let target = 26501365i64;
+        let n = (target - 65) / 131;
+        assert_eq!(poly.eval_integer(n), R::from(f(target)));
+    }
+}
@@ -0,0 +1,334 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::{NumConsts, gcd};
+
+/// The operations `Rational` needs from its component type. Implemented for
+/// every signed primitive integer as well as `BigInt`.
+pub trait SignedInt:
+    Clone
+    + Ord
+    + NumConsts
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + Neg<Output = Self>
+{
+}
+
+impl<T> SignedInt for T where
+    T: Clone
+        + Ord
+        + NumConsts
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Rem<Output = T>
+        + Neg<Output = T>
+{
+}
+
+/// Always stored in lowest terms with a positive denominator, so the derived
+/// equality and hashing are exact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational<T> {
+    num: T,
+    den: T,
+}
+
+impl<T: SignedInt> Rational<T> {
+    pub fn new(num: T, den: T) -> Self {
+        if den == T::ZERO {
+            panic!("attempt to create a rational with a zero denominator");
+        }
+        let (num, den) = if den < T::ZERO {
+            (-num, -den)
+        } else {
+            (num, den)
+        };
+        let g = abs(gcd(num.clone(), den.clone()));
+        Self {
+            num: num / g.clone(),
+            den: den / g,
+        }
+    }
+
+    pub fn from_integer(x: T) -> Self {
+        Self {
+            num: x,
+            den: T::ONE,
+        }
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.num
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == T::ZERO
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.num < T::ZERO
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::ONE
+    }
+
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then(|| self.num.clone())
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            num: abs(self.num.clone()),
+            den: self.den.clone(),
+        }
+    }
+
+    pub fn recip(&self) -> Self {
+        Self::new(self.den.clone(), self.num.clone())
+    }
+
+    pub fn floor(&self) -> T {
+        let q = self.num.clone() / self.den.clone();
+        if self.num < T::ZERO && !self.is_integer() {
+            q - T::ONE
+        } else {
+            q
+        }
+    }
+
+    pub fn ceil(&self) -> T {
+        let q = self.num.clone() / self.den.clone();
+        if self.num > T::ZERO && !self.is_integer() {
+            q + T::ONE
+        } else {
+            q
+        }
+    }
+}
+
+fn abs<T: SignedInt>(x: T) -> T {
+    if x < T::ZERO { -x } else { x }
+}
+
+impl<T: NumConsts> NumConsts for Rational<T> {
+    const ZERO: Self = Rational {
+        num: T::ZERO,
+        den: T::ONE,
+    };
+    const ONE: Self = Rational {
+        num: T::ONE,
+        den: T::ONE,
+    };
+}
+
+impl<T: SignedInt> From<T> for Rational<T> {
+    fn from(x: T) -> Self {
+        Self::from_integer(x)
+    }
+}
+
+impl<T: SignedInt> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: SignedInt> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive so cross multiplying keeps the order
+        (self.num.clone() * other.den.clone()).cmp(&(other.num.clone() * self.den.clone()))
+    }
+}
+
+impl<T: SignedInt> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        let g = gcd(self.den.clone(), rhs.den.clone());
+        let lhs_scale = rhs.den.clone() / g.clone();
+        let rhs_scale = self.den.clone() / g;
+        Self::new(
+            self.num * lhs_scale.clone() + rhs.num * rhs_scale,
+            self.den * lhs_scale,
+        )
+    }
+}
+
+impl<T: SignedInt> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T: SignedInt> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // Cross reduce first to keep the intermediates small
+        let g1 = abs(gcd(self.num.clone(), rhs.den.clone()));
+        let g2 = abs(gcd(rhs.num.clone(), self.den.clone()));
+        Self::new(
+            (self.num / g1.clone()) * (rhs.num / g2.clone()),
+            (self.den / g2) * (rhs.den / g1),
+        )
+    }
+}
+
+impl<T: SignedInt> Div for Rational<T> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.recip()
+    }
+}
+
+impl<T: SignedInt> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl<T: SignedInt> AddAssign for Rational<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = self.clone() + rhs;
+    }
+}
+
+impl<T: SignedInt> SubAssign for Rational<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = self.clone() - rhs;
+    }
+}
+
+impl<T: SignedInt> MulAssign for Rational<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.clone() * rhs;
+    }
+}
+
+impl<T: SignedInt> DivAssign for Rational<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = self.clone() / rhs;
+    }
+}
+
+impl<T: SignedInt> std::iter::Sum for Rational<T> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<T: SignedInt> std::iter::Product for Rational<T> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<T: SignedInt + Display> Display for Rational<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl<T: SignedInt + FromStr> FromStr for Rational<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |part: &str| {
+            part.trim()
+                .parse::<T>()
+                .map_err(|_| format!("Invalid rational {s}"))
+        };
+        match s.split_once('/') {
+            Some((num, den)) => {
+                let den = parse(den)?;
+                if den == T::ZERO {
+                    Err(format!("Zero denominator in {s}"))?
+                }
+                Ok(Self::new(parse(num)?, den))
+            }
+            None => Ok(Self::from_integer(parse(s)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::quant::BigInt;
+
+    type R = Rational<i64>;
+
+    fn r(num: i64, den: i64) -> R {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn test_normalise() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(1, -2), r(-1, 2));
+        assert_eq!(r(-3, -9), r(1, 3));
+        assert_eq!(r(0, -5), R::ZERO);
+        assert_eq!(*r(6, -4).denom(), 2);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(1, 3), r(1, 6));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(-4, 9), r(-3, 2));
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert_eq!((1..=4).map(|i| r(1, i)).sum::<R>(), r(25, 12));
+    }
+
+    #[test]
+    fn test_floor_ceil() {
+        assert_eq!(r(7, 2).floor(), 3);
+        assert_eq!(r(7, 2).ceil(), 4);
+        assert_eq!(r(-7, 2).floor(), -4);
+        assert_eq!(r(-7, 2).ceil(), -3);
+        assert_eq!(r(4, 2).floor(), 2);
+        assert_eq!(r(4, 2).ceil(), 2);
+    }
+
+    #[test]
+    fn test_parse_display() {
+        assert_eq!("3/-6".parse::<R>().unwrap(), r(-1, 2));
+        assert_eq!("5".parse::<R>().unwrap(), R::from(5));
+        assert!("1/0".parse::<R>().is_err());
+        assert_eq!(r(-10, 4).to_string(), "-5/2");
+        assert_eq!(r(10, 5).to_string(), "2");
+
+        let big = Rational::new(BigInt::from(10).pow(30), BigInt::from(-4));
+        assert_eq!(big.to_string(), "-250000000000000000000000000000");
+    }
+}
@@ -0,0 +1,119 @@
+use std::ops::{Add, Mul, Rem};
+
+use super::{NumConsts, matrix::Matrix};
+
+/// Companion matrix of `a[n] = coeffs[0] * a[n - 1] + ... + coeffs[k - 1] * a[n - k]`.
+/// It maps the state `[a[n - 1], ..., a[n - k]]` to `[a[n], ..., a[n - k + 1]]`.
+pub fn companion<T>(coeffs: &[T]) -> Matrix<T>
+where
+    T: Clone + NumConsts,
+{
+    let k = coeffs.len();
+    let mut m = Matrix::zeros(k, k);
+    for (j, c) in coeffs.iter().enumerate() {
+        m[(0, j)] = c.clone();
+    }
+    for i in 1..k {
+        m[(i, i - 1)] = T::ONE;
+    }
+    m
+}
+
+// The state vector for the companion matrix is newest first
+fn initial_state<T: Clone>(initial: &[T]) -> Vec<T> {
+    initial.iter().rev().cloned().collect()
+}
+
+/// The `n`th term (counting from zero) of the recurrence with the given
+/// coefficients and first `coeffs.len()` terms. With no coefficients every
+/// term is the empty sum, zero.
+pub fn nth_term<T>(coeffs: &[T], initial: &[T], n: u64) -> T
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T>,
+{
+    assert_eq!(
+        coeffs.len(),
+        initial.len(),
+        "Need one initial term per coefficient"
+    );
+    let k = initial.len() as u64;
+    if n < k {
+        return initial[n as usize].clone();
+    }
+    companion(coeffs)
+        .pow(n - k + 1)
+        .mul_vec(&initial_state(initial))
+        .into_iter()
+        .next()
+        .unwrap_or(T::ZERO)
+}
+
+/// As `nth_term`, reduced modulo `m`.
+pub fn nth_term_mod<T>(coeffs: &[T], initial: &[T], n: u64, m: T) -> T
+where
+    T: Clone + NumConsts + Add<Output = T> + Mul<Output = T> + Rem<Output = T>,
+{
+    assert_eq!(
+        coeffs.len(),
+        initial.len(),
+        "Need one initial term per coefficient"
+    );
+    let k = initial.len() as u64;
+    if n < k {
+        return initial[n as usize].clone() % m;
+    }
+    let state: Vec<T> = initial_state(initial)
+        .into_iter()
+        .map(|x| x % m.clone())
+        .collect();
+    companion(coeffs)
+        .pow_mod(n - k + 1, m.clone())
+        .mul_vec_mod(&state, m.clone())
+        .into_iter()
+        .next()
+        .unwrap_or(T::ZERO)
+        % m
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fibonacci() {
+        assert_eq!(nth_term(&[1u64, 1], &[0, 1], 0), 0);
+        assert_eq!(nth_term(&[1u64, 1], &[0, 1], 1), 1);
+        assert_eq!(nth_term(&[1u64, 1], &[0, 1], 10), 55);
+        assert_eq!(
+            nth_term_mod(&[1u64, 1], &[0, 1], 1_000_000_000_000_000, 1_000_000_007),
+            648325137
+        );
+    }
+
+    #[test]
+    fn test_tribonacci() {
+        let coeffs = [1i64, 1, 1];
+        let initial = [0, 0, 1];
+        assert_eq!(nth_term(&coeffs, &initial, 10), 81);
+        assert_eq!(nth_term(&coeffs, &initial, 37), 1132436852);
+        assert_eq!(companion(&coeffs).mul_vec(&[1, 0, 0]), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn test_no_coefficients() {
+        assert_eq!(nth_term::<i64>(&[], &[], 0), 0);
+        assert_eq!(nth_term::<i64>(&[], &[], 5), 0);
+        assert_eq!(nth_term_mod::<u64>(&[], &[], 5, 7), 0);
+    }
+
+    #[test]
+    fn test_large_modulus() {
+        // Products of residues near 2^64 only fit u128 one at a time
+        let m = u128::from(u64::MAX) - 58;
+        let coeffs = [m - 1, m - 1, m - 1];
+        let initial = [m - 1, m - 2, m - 3];
+        // With every coefficient -1 mod m, a[3] = -(a[2] + a[1] + a[0]) = 6
+        assert_eq!(nth_term_mod(&coeffs, &initial, 3, m), 6);
+        assert_eq!(nth_term_mod(&coeffs, &initial, 4, m), m - 1);
+    }
+}